@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Err(e) = itoa_example::fuzz::check_bytes(data) {
+        panic!("{}", e);
+    }
+});
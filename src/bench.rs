@@ -0,0 +1,700 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+use rand::distributions::uniform::SampleUniform;
+use rand::distributions::Uniform;
+use rand::Rng;
+
+use itoa_example::{
+    compare, reject_outliers, stats, FormatFloat, FormatInteger, OutlierFilter, ParseInteger,
+    SimpleDisplay, Stats, Verdict,
+};
+
+use crate::dataset::{group_by_digits, InputFile};
+use crate::distribution::Distribution;
+use crate::output::{DigitsResult, ImplResult, Output};
+use crate::registry::{
+    float_implementations, implementations_for, len_counters, parsers, FormatTrait, Implementation,
+    LenCounter, Parser,
+};
+
+/// ベンチマークの設定
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// 1回の計測で書き出す値の数
+    pub size: usize,
+    /// 各桁数での計測回数
+    pub iter: usize,
+    /// 計測する最小の桁数
+    pub min_digits: u32,
+    /// 計測する最大の桁数 (型の最大桁数を超える分は無視する)
+    pub max_digits: u32,
+    /// 各桁数での計測時間の上限 (超えた時点で`iter`回に満たなくても打ち切る)
+    pub time_budget: Option<Duration>,
+    /// 各桁数で計測の前に捨てる計測の回数
+    pub warmup: usize,
+    /// 外れ値の除去方法
+    pub outliers: OutlierFilter,
+    /// 計測する入力値の分布 (この順に結果の行を出す)
+    pub distributions: Vec<Distribution>,
+    /// 乱数の代わりに値を読み込むファイル (指定した場合は`distributions`を無視する)
+    pub input: Option<InputFile>,
+    /// 計測する書式トレイト
+    pub format_trait: FormatTrait,
+    /// 書式化の代わりに解析を計測するかどうか (`format_trait`は無視する)
+    pub parse: bool,
+    /// 書式化の代わりに桁数の計算を計測するかどうか (`u64`のみ、`format_trait`は無視する)
+    pub len: bool,
+    /// 浮動小数点数の計測で、行ごとに進める10進の指数の幅 (1以上1000以下)
+    pub exp_step: u32,
+}
+
+/// 型名`int_type`の整数型(または浮動小数点数型)について計測する
+///
+/// `output`が与えられた場合は、結果を順次書き出す。
+pub fn run_bench(
+    rng: &mut impl Rng,
+    int_type: &str,
+    config: &Config,
+    output: Option<&mut Output>,
+) -> Result<Vec<DigitsResult>> {
+    if config.len {
+        if int_type != "u64" {
+            bail!("--len is only supported for u64");
+        }
+        return bench_suite::<u64>(rng, config, &len_counters(), output);
+    }
+
+    match int_type {
+        "u8" => bench_type::<u8>(rng, config, output),
+        "u16" => bench_type::<u16>(rng, config, output),
+        "u32" => bench_type::<u32>(rng, config, output),
+        "u64" => bench_type::<u64>(rng, config, output),
+        "usize" => bench_type::<usize>(rng, config, output),
+        "u128" => bench_type::<u128>(rng, config, output),
+        "i8" => bench_type::<i8>(rng, config, output),
+        "i16" => bench_type::<i16>(rng, config, output),
+        "i32" => bench_type::<i32>(rng, config, output),
+        "i64" => bench_type::<i64>(rng, config, output),
+        "isize" => bench_type::<isize>(rng, config, output),
+        "i128" => bench_type::<i128>(rng, config, output),
+        "f32" => bench_float::<f32>(rng, config, output),
+        "f64" => bench_float::<f64>(rng, config, output),
+        _ => unreachable!(),
+    }
+}
+
+/// `T`で表せる桁数のうち、設定された範囲について計測する
+fn bench_type<T: BenchInteger>(
+    rng: &mut impl Rng,
+    config: &Config,
+    output: Option<&mut Output>,
+) -> Result<Vec<DigitsResult>> {
+    let suite: Box<dyn Suite<T>> = if config.parse {
+        Box::new(parsers::<T>())
+    } else {
+        Box::new(implementations_for::<T>(config.format_trait))
+    };
+    bench_suite(rng, config, suite.as_ref(), output)
+}
+
+/// 実装の集まり`impls`を、設定された入力値について計測する
+fn bench_suite<T: BenchInteger>(
+    rng: &mut impl Rng,
+    config: &Config,
+    impls: &dyn Suite<T>,
+    mut output: Option<&mut Output>,
+) -> Result<Vec<DigitsResult>> {
+    // 読み込みに失敗した場合に表の見出しだけが出力されないよう、先に読み込む
+    let loaded = match &config.input {
+        Some(input) => {
+            let values: Vec<T> = input.load()?;
+            eprintln!(
+                "Loaded {} values from {}",
+                values.len(),
+                input.path.display()
+            );
+            Some((input, values))
+        }
+        None => None,
+    };
+
+    let names = impls.names();
+    if let Some(output) = output.as_deref_mut() {
+        output.begin(&names);
+    }
+
+    let mut results = Vec::new();
+
+    if let Some((input, values)) = loaded {
+        let mut groups = vec![("all".to_string(), values.clone())];
+        groups.extend(group_by_digits(&values));
+        for (label, group) in groups {
+            let title = if label == "all" {
+                format!("all values in {}", input.path.display())
+            } else {
+                format!("{} digits in {}", label, input.path.display())
+            };
+            let range = (
+                group.iter().min().unwrap().to_string(),
+                group.iter().max().unwrap().to_string(),
+            );
+            let result = bench_with(rng, config, impls, &title, label, range, |_| group.clone());
+            if let Some(output) = output.as_deref_mut() {
+                output.row(&result);
+            }
+            results.push(result);
+        }
+    }
+
+    let distributions: &[Distribution] = if config.input.is_some() {
+        &[]
+    } else {
+        &config.distributions
+    };
+    for dist in distributions.iter().copied() {
+        if dist != Distribution::Digits {
+            let range = dist.range::<T>();
+            let title = format!("{} distribution", dist.name());
+            let label = dist.name().to_string();
+            let result = bench_with(rng, config, impls, &title, label, range, |rng| {
+                dist.generate(rng, config.size)
+            });
+            if let Some(output) = output.as_deref_mut() {
+                output.row(&result);
+            }
+            results.push(result);
+            continue;
+        }
+
+        let max_digits = config.max_digits.min(T::MAX_LEN as u32);
+        for digits in config.min_digits..=max_digits {
+            // 符号付き整数型では最大桁数の負の値が存在しないことがある (i64 の20桁など)
+            if 10u128.pow(digits - 1) > T::MAX_MAGNITUDE {
+                break;
+            }
+            let result = bench_for_digits(rng, config, impls, digits);
+            if let Some(output) = output.as_deref_mut() {
+                output.row(&result);
+            }
+            results.push(result);
+        }
+    }
+
+    if let Some(output) = output {
+        output.end();
+    }
+    Ok(results)
+}
+
+/// 浮動小数点数型`F`について、10進の指数ごとに計測する
+///
+/// 行のラベルは`1e-5`のように、その行の値の範囲の下限とする。
+/// 書式トレイトは`Display`だけを扱い、`--input`と`--parse`には対応しない。
+fn bench_float<F: BenchFloat>(
+    rng: &mut impl Rng,
+    config: &Config,
+    mut output: Option<&mut Output>,
+) -> Result<Vec<DigitsResult>> {
+    if config.input.is_some() || config.parse || config.format_trait != FormatTrait::Display {
+        bail!("--input, --parse and --trait are not supported for floating-point types");
+    }
+
+    let impls = FloatSuite(float_implementations::<F>());
+    if let Some(output) = output.as_deref_mut() {
+        output.begin(&impls.names());
+    }
+
+    let mut results = Vec::new();
+    let mut exp = F::MIN_EXP;
+    while exp <= F::MAX_EXP {
+        let label = format!("1e{}", exp);
+        let range = (label.clone(), format!("1e{}", exp + 1));
+        let title = format!("values around {}", label);
+        let result = bench_with(rng, config, &impls, &title, label, range, |rng| {
+            (0..config.size)
+                .map(|_| F::random_in_decade(rng, exp))
+                .collect()
+        });
+        if let Some(output) = output.as_deref_mut() {
+            output.row(&result);
+        }
+        results.push(result);
+        exp += config.exp_step as i32;
+    }
+
+    if let Some(output) = output {
+        output.end();
+    }
+    Ok(results)
+}
+
+/// `digits`桁の値について計測する
+///
+/// 符号付き整数型の場合は、`digits`桁の負の値について計測する。
+fn bench_for_digits<T: BenchInteger>(
+    rng: &mut impl Rng,
+    config: &Config,
+    impls: &dyn Suite<T>,
+    digits: u32,
+) -> DigitsResult {
+    let value_min = 10u128.pow(digits - 1);
+    let value_max = value_min
+        .checked_mul(10)
+        .map_or(u128::MAX, |v| v - 1)
+        .min(T::MAX_MAGNITUDE);
+
+    let (label, range_min, range_max) = if T::SIGNED {
+        (
+            format!("-{}", digits),
+            format!("-{}", value_max),
+            format!("-{}", value_min),
+        )
+    } else {
+        (
+            digits.to_string(),
+            value_min.to_string(),
+            value_max.to_string(),
+        )
+    };
+
+    let (low, high) = if T::SIGNED {
+        (T::from_magnitude(value_max), T::from_magnitude(value_min))
+    } else {
+        (T::from_magnitude(value_min), T::from_magnitude(value_max))
+    };
+
+    bench_with(
+        rng,
+        config,
+        impls,
+        &format!("{} digits", label),
+        label,
+        (range_min, range_max),
+        |rng| gen_values(rng, config.size, low, high),
+    )
+}
+
+/// `gen`で生成した値の列について計測する
+///
+/// `title`は進捗の表示に、`label`は結果の`Digits`列に、`range`は値の範囲として結果に使う。
+fn bench_with<T, R, G>(
+    rng: &mut R,
+    config: &Config,
+    impls: &dyn Suite<T>,
+    title: &str,
+    label: String,
+    range: (String, String),
+    mut gen: G,
+) -> DigitsResult
+where
+    R: Rng,
+    G: FnMut(&mut R) -> Vec<T>,
+{
+    let (range_min, range_max) = range;
+    eprintln!("For {} ({} ~ {}):", title, range_min, range_max);
+
+    let names = impls.names();
+
+    let mut checked_values = 0;
+    let mut measured_values = 0;
+    let mut mismatches = vec![0; names.len()];
+
+    // 確保直後のバッファやキャッシュの影響を避けるため、最初の数回は捨てる
+    for _ in 0..config.warmup {
+        let values = gen(rng);
+        for (mis, m) in mismatches.iter_mut().zip(impls.measure(&values)) {
+            if !m.matches {
+                *mis += 1;
+            }
+        }
+        checked_values += values.len();
+    }
+
+    let mut times = vec![Vec::<f64>::new(); names.len()];
+    let mut bytes = vec![0usize; names.len()];
+
+    let start = Instant::now();
+    for _ in 0..config.iter {
+        if let Some(budget) = config.time_budget {
+            if start.elapsed() >= budget && !times[0].is_empty() {
+                break;
+            }
+        }
+
+        let values = gen(rng);
+        let measurements = impls.measure(&values);
+        for (i, m) in measurements.into_iter().enumerate() {
+            times[i].push(m.time);
+            bytes[i] += m.bytes;
+            if !m.matches {
+                mismatches[i] += 1;
+            }
+        }
+        checked_values += values.len();
+        measured_values += values.len();
+    }
+
+    let all_stats: Vec<Stats> = times
+        .iter()
+        .map(|ts| stats(&reject_outliers(ts, config.outliers)))
+        .collect();
+    let reference = names.iter().position(|&(_, is_ref)| is_ref).unwrap();
+    let reference_name = names[reference].0;
+    let values_per_iter = measured_values / times[reference].len();
+
+    let mut results = Vec::with_capacity(names.len());
+    for (i, (ts, s)) in times.into_iter().zip(all_stats.iter()).enumerate() {
+        let (name, is_reference) = names[i];
+        let bytes_per_iter = bytes[i] as f64 / ts.len() as f64;
+        eprintln!(
+            "    {:<7} avg = {:.3}s (95% CI {:.3}s ~ {:.3}s), min = {:.3}s, max = {:.3}s",
+            format!("{}:", name),
+            s.avg,
+            s.ci_low,
+            s.ci_high,
+            s.min,
+            s.max
+        );
+        eprintln!(
+            "            median = {:.3}s, sd = {:.3}s, p5 = {:.3}s, p95 = {:.3}s",
+            s.median, s.std_dev, s.p5, s.p95
+        );
+        if config.outliers != OutlierFilter::None {
+            eprintln!(
+                "            dropped {} of {} samples as outliers",
+                ts.len() - s.n,
+                ts.len()
+            );
+        }
+
+        let verdict = if is_reference {
+            None
+        } else {
+            let verdict = compare(s, &all_stats[reference]);
+            let summary = match verdict {
+                Verdict::Same => "no significant difference".to_string(),
+                _ => format!("significantly {} (p < 0.05)", verdict),
+            };
+            eprintln!("            vs {}: {}", reference_name, summary);
+            Some(verdict)
+        };
+        if mismatches[i] > 0 {
+            eprintln!(
+                "            OUTPUT MISMATCH against {} in {} iterations",
+                reference_name, mismatches[i]
+            );
+        }
+
+        results.push(ImplResult {
+            name,
+            is_reference,
+            samples: ts,
+            stats: *s,
+            verdict,
+            checked_values,
+            mismatched_iters: mismatches[i],
+            values_per_iter,
+            bytes_per_iter,
+        });
+        let r = results.last().unwrap();
+        eprintln!(
+            "            {:.3} ns/value, {:.0} values/s, {:.3} MB/s",
+            r.ns_per_value(),
+            r.values_per_sec(),
+            r.mb_per_sec()
+        );
+    }
+
+    DigitsResult {
+        label,
+        value_min: range_min,
+        value_max: range_max,
+        impls: results,
+    }
+}
+
+/// `min`以上`max`以下の一様乱数を`size`個生成する
+fn gen_values<T: SampleUniform>(rng: &mut impl Rng, size: usize, min: T, max: T) -> Vec<T> {
+    let dist = Uniform::new_inclusive(min, max);
+    (0..size).map(|_| rng.sample(&dist)).collect()
+}
+
+/// 同じ値の列について比べる実装の集まり
+trait Suite<T> {
+    /// 各実装の名前と、基準の実装かどうか
+    fn names(&self) -> Vec<(&'static str, bool)>;
+
+    /// `values`について各実装を1回ずつ計測する
+    fn measure(&self, values: &[T]) -> Vec<Measurement>;
+}
+
+impl<T: FormatInteger> Suite<T> for Vec<Box<dyn Implementation<T>>> {
+    fn names(&self) -> Vec<(&'static str, bool)> {
+        self.iter()
+            .map(|imp| (imp.name(), imp.is_reference()))
+            .collect()
+    }
+
+    fn measure(&self, values: &[T]) -> Vec<Measurement> {
+        bench_values(self, values, T::MAX_LEN)
+    }
+}
+
+/// 浮動小数点数の実装の集まり
+struct FloatSuite<F>(Vec<Box<dyn Implementation<F>>>);
+
+impl<F: FormatFloat> Suite<F> for FloatSuite<F> {
+    fn names(&self) -> Vec<(&'static str, bool)> {
+        self.0
+            .iter()
+            .map(|imp| (imp.name(), imp.is_reference()))
+            .collect()
+    }
+
+    fn measure(&self, values: &[F]) -> Vec<Measurement> {
+        // 小数表記の長さは指数で大きく変わるので、先頭の値から見積もる (仮数は高々17桁)
+        let len = values.first().map_or(0, |v| v.to_string().len());
+        bench_values(&self.0, values, len + 17)
+    }
+}
+
+impl<T: BenchInteger> Suite<T> for Vec<Box<dyn Parser<T>>> {
+    fn names(&self) -> Vec<(&'static str, bool)> {
+        self.iter().map(|p| (p.name(), p.is_reference())).collect()
+    }
+
+    fn measure(&self, values: &[T]) -> Vec<Measurement> {
+        bench_parse(self, values)
+    }
+}
+
+impl Suite<u64> for Vec<Box<dyn LenCounter>> {
+    fn names(&self) -> Vec<(&'static str, bool)> {
+        self.iter().map(|c| (c.name(), c.is_reference())).collect()
+    }
+
+    fn measure(&self, values: &[u64]) -> Vec<Measurement> {
+        bench_len(self, values)
+    }
+}
+
+/// 1回の計測での、ある実装の結果
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+    /// 所要時間(秒)
+    time: f64,
+    /// 書き出した(解析の場合は読んだ、桁数の場合は数えた)バイト数
+    bytes: usize,
+    /// 出力が正しかったかどうか
+    matches: bool,
+}
+
+/// 同じ値の列を各実装で書き出し、それぞれの所要時間を返す
+///
+/// 各実装の出力が基準の実装の出力と一致するかも確認する。
+/// `max_len`は出力の領域を確保するための、値1個あたりの長さの見積もり。
+fn bench_values<T>(
+    impls: &[Box<dyn Implementation<T>>],
+    values: &[T],
+    max_len: usize,
+) -> Vec<Measurement> {
+    // 区切り文字の分を加える
+    let capacity = (max_len + 2) * values.len();
+
+    let mut times = Vec::with_capacity(impls.len());
+    let mut outputs = Vec::with_capacity(impls.len());
+    for imp in impls.iter() {
+        let mut w = Vec::<u8>::with_capacity(capacity);
+        let start = Instant::now();
+        imp.write_all(values, &mut w);
+        times.push(start.elapsed().as_secs_f64());
+        outputs.push(w);
+    }
+
+    let reference = impls.iter().position(|imp| imp.is_reference()).unwrap();
+    times
+        .into_iter()
+        .zip(outputs.iter())
+        .map(|(time, w)| Measurement {
+            time,
+            bytes: w.len(),
+            matches: *w == outputs[reference],
+        })
+        .collect()
+}
+
+/// 同じ値の列の桁数を各実装で求め、それぞれの所要時間を返す
+///
+/// 各実装の結果が基準の実装の結果と一致するかも確認する。
+fn bench_len(counters: &[Box<dyn LenCounter>], values: &[u64]) -> Vec<Measurement> {
+    let mut times = Vec::with_capacity(counters.len());
+    let mut outputs = Vec::with_capacity(counters.len());
+    for counter in counters.iter() {
+        let mut out = Vec::with_capacity(values.len());
+        let start = Instant::now();
+        counter.count_all(values, &mut out);
+        times.push(start.elapsed().as_secs_f64());
+        outputs.push(out);
+    }
+
+    let reference = counters.iter().position(|c| c.is_reference()).unwrap();
+    times
+        .into_iter()
+        .zip(outputs.iter())
+        .map(|(time, out)| Measurement {
+            time,
+            bytes: out.iter().map(|&l| usize::from(l)).sum(),
+            matches: *out == outputs[reference],
+        })
+        .collect()
+}
+
+/// 値の列を`SimpleDisplay`でカンマ区切りに書き出し、それを各実装で解析した所要時間を返す
+///
+/// 解析結果が元の値の列と一致するか(往復できるか)も確認する。
+fn bench_parse<T: BenchInteger>(parsers: &[Box<dyn Parser<T>>], values: &[T]) -> Vec<Measurement> {
+    let mut input = Vec::with_capacity((T::MAX_LEN + 2) * values.len());
+    for v in values.iter() {
+        SimpleDisplay(*v).append_to(&mut input);
+        input.push(b',');
+    }
+    input.pop();
+    let input = String::from_utf8(input).unwrap();
+
+    parsers
+        .iter()
+        .map(|parser| {
+            let mut out = Vec::with_capacity(values.len());
+            let start = Instant::now();
+            parser.parse_all(&input, &mut out);
+            Measurement {
+                time: start.elapsed().as_secs_f64(),
+                bytes: input.len(),
+                matches: out == values,
+            }
+        })
+        .collect()
+}
+
+/// ベンチマーク対象の整数型
+pub trait BenchInteger: FormatInteger + ParseInteger + SampleUniform + Ord + FromStr {
+    /// 符号付き整数型かどうか
+    const SIGNED: bool;
+    /// 表せる絶対値の最大値
+    const MAX_MAGNITUDE: u128;
+    /// 表せる正の値の最大値
+    const MAX_POSITIVE: u128;
+    /// 型の最小値
+    const MIN_VALUE: Self;
+    /// 型の最大値
+    const MAX_VALUE: Self;
+
+    /// 絶対値から値を作る。符号付き整数型の場合は負の値になる。
+    fn from_magnitude(m: u128) -> Self;
+
+    /// 符号と絶対値から値を作る。符号なし整数型では`negative`を無視する。
+    fn from_parts(negative: bool, m: u128) -> Self;
+}
+
+macro_rules! impl_bench_unsigned {
+    ($($t:ty),*) => {$(
+        impl BenchInteger for $t {
+            const SIGNED: bool = false;
+            const MAX_MAGNITUDE: u128 = <$t>::MAX as u128;
+            const MAX_POSITIVE: u128 = <$t>::MAX as u128;
+            const MIN_VALUE: Self = <$t>::MIN;
+            const MAX_VALUE: Self = <$t>::MAX;
+
+            fn from_magnitude(m: u128) -> Self {
+                m as $t
+            }
+
+            fn from_parts(_negative: bool, m: u128) -> Self {
+                m as $t
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_bench_signed {
+    ($($t:ty),*) => {$(
+        impl BenchInteger for $t {
+            const SIGNED: bool = true;
+            const MAX_MAGNITUDE: u128 = <$t>::MIN.unsigned_abs() as u128;
+            const MAX_POSITIVE: u128 = <$t>::MAX as u128;
+            const MIN_VALUE: Self = <$t>::MIN;
+            const MAX_VALUE: Self = <$t>::MAX;
+
+            fn from_magnitude(m: u128) -> Self {
+                // MIN の絶対値は wrapping_neg で MIN に戻る
+                (m as $t).wrapping_neg()
+            }
+
+            fn from_parts(negative: bool, m: u128) -> Self {
+                if negative {
+                    Self::from_magnitude(m)
+                } else {
+                    m as $t
+                }
+            }
+        }
+    )*};
+}
+
+impl_bench_unsigned!(u8, u16, u32, u64, usize, u128);
+impl_bench_signed!(i8, i16, i32, i64, isize, i128);
+
+/// ベンチマーク対象の浮動小数点数型
+pub trait BenchFloat: FormatFloat {
+    /// 計測する最小の10進の指数 (最小の非正規化数を含む範囲)
+    const MIN_EXP: i32;
+    /// 計測する最大の10進の指数
+    const MAX_EXP: i32;
+
+    /// およそ`10^exp`以上`10^(exp + 1)`未満の正の値を1つ生成する
+    ///
+    /// 2進の指数を一様に選び、仮数部のビットを乱数で埋める。
+    /// 範囲の端では2進の指数の区切りの分だけ外れることがある。
+    fn random_in_decade(rng: &mut impl Rng, exp: i32) -> Self;
+}
+
+macro_rules! impl_bench_float {
+    ($($t:ty, $bits:ty, $mantissa_bits:expr, $bias:expr, $min_exp:expr, $max_exp:expr;)*) => {$(
+        impl BenchFloat for $t {
+            const MIN_EXP: i32 = $min_exp;
+            const MAX_EXP: i32 = $max_exp;
+
+            fn random_in_decade(rng: &mut impl Rng, exp: i32) -> Self {
+                let low = (f64::from(exp) * std::f64::consts::LOG2_10).floor() as i32;
+                let high = (f64::from(exp + 1) * std::f64::consts::LOG2_10).floor() as i32;
+                // 値が 2^e2 以上 2^(e2 + 1) 未満になる2進の指数
+                let e2 = rng.gen_range(low, high.max(low + 1));
+                let mantissa: $bits = rng.gen::<$bits>() & ((1 << $mantissa_bits) - 1);
+
+                let biased = e2 + $bias;
+                let bits = if biased >= 1 {
+                    // 最大の指数(無限大とNaN)を避ける
+                    let biased = biased.min(2 * $bias) as $bits;
+                    biased << $mantissa_bits | mantissa
+                } else {
+                    // 非正規化数は仮数部の最上位ビットの位置で大きさが決まる
+                    let shift = biased + $mantissa_bits - 1;
+                    if shift < 0 {
+                        1
+                    } else {
+                        1 << shift | mantissa >> ($mantissa_bits - shift)
+                    }
+                };
+                <$t>::from_bits(bits)
+            }
+        }
+    )*};
+}
+
+impl_bench_float! {
+    f32, u32, 23, 127, -45, 38;
+    f64, u64, 52, 1023, -323, 308;
+}
@@ -0,0 +1,308 @@
+use std::fmt::Write;
+
+use crate::table::{ResultTable, TableRow};
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 500.0;
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 140.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+
+/// 系列の色
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+];
+
+/// 桁数を横軸、計測時間を縦軸にした折れ線グラフをSVGで描く
+///
+/// 実装ごとに平均を折れ線で、最小から最大の範囲を帯で描く。
+/// 負の値の行(`Digits`が`-3`など)は桁数の絶対値の位置に描く。
+/// 浮動小数点数の結果(`Digits`が`1e-5`など)は10進の指数を横軸にする。
+pub fn render_svg(table: &ResultTable, title: &str) -> String {
+    let points: Vec<(f64, &TableRow)> = table
+        .rows
+        .iter()
+        .filter_map(|r| label_x(&r.label).map(|x| (x, r)))
+        .collect();
+    let by_exponent = table.rows.iter().any(|r| r.label.starts_with("1e"));
+
+    let x_min = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let x_max = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let (x_min, x_max) = if points.is_empty() {
+        (0.0, 1.0)
+    } else if x_min == x_max {
+        (x_min - 1.0, x_max + 1.0)
+    } else {
+        (x_min, x_max)
+    };
+
+    let y_data_max = points
+        .iter()
+        .flat_map(|(_, r)| {
+            table
+                .names
+                .iter()
+                .filter_map(move |n| r.get(n, "Max").or_else(|| r.get(n, "Avg")))
+        })
+        .fold(0.0, f64::max);
+    let y_step = nice_step(if y_data_max > 0.0 { y_data_max } else { 1.0 } / 5.0);
+    let y_max = (y_data_max / y_step).ceil().max(1.0) * y_step;
+    let (unit, scale) = time_unit(y_max);
+
+    let plot_w = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_h = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let sx = |x: f64| MARGIN_LEFT + (x - x_min) / (x_max - x_min) * plot_w;
+    let sy = |y: f64| MARGIN_TOP + plot_h - y / y_max * plot_h;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+        w = WIDTH,
+        h = HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        WIDTH, HEIGHT
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="24" text-anchor="middle" font-size="16">{}</text>"#,
+        MARGIN_LEFT + plot_w / 2.0,
+        xml_escape(title)
+    )
+    .unwrap();
+
+    // 縦軸の目盛りと補助線
+    let mut y = 0.0;
+    while y <= y_max + y_step / 2.0 {
+        writeln!(
+            svg,
+            r##"<line x1="{x1:.1}" y1="{y:.1}" x2="{x2:.1}" y2="{y:.1}" stroke="#ddd"/><text x="{tx:.1}" y="{ty:.1}" text-anchor="end">{label}</text>"##,
+            x1 = MARGIN_LEFT,
+            x2 = MARGIN_LEFT + plot_w,
+            y = sy(y),
+            tx = MARGIN_LEFT - 6.0,
+            ty = sy(y) + 4.0,
+            label = format_tick(y * scale)
+        )
+        .unwrap();
+        y += y_step;
+    }
+
+    // 横軸の目盛り
+    let x_step = if x_max - x_min > 40.0 {
+        nice_step((x_max - x_min) / 20.0)
+    } else if x_max - x_min > 20.0 {
+        2.0
+    } else {
+        1.0
+    };
+    let mut x = x_min;
+    while x <= x_max {
+        writeln!(
+            svg,
+            r##"<line x1="{x:.1}" y1="{y1:.1}" x2="{x:.1}" y2="{y2:.1}" stroke="#333"/><text x="{x:.1}" y="{ty:.1}" text-anchor="middle">{label}</text>"##,
+            x = sx(x),
+            y1 = MARGIN_TOP + plot_h,
+            y2 = MARGIN_TOP + plot_h + 5.0,
+            ty = MARGIN_TOP + plot_h + 20.0,
+            label = x
+        )
+        .unwrap();
+        x += x_step;
+    }
+
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#333"/>"##,
+        MARGIN_LEFT, MARGIN_TOP, plot_w, plot_h
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+        MARGIN_LEFT + plot_w / 2.0,
+        HEIGHT - 16.0,
+        if by_exponent {
+            "Decimal exponent"
+        } else {
+            "Digits"
+        }
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<text x="18" y="{y}" text-anchor="middle" transform="rotate(-90 18 {y})">Time per iteration ({unit})</text>"#,
+        y = MARGIN_TOP + plot_h / 2.0,
+        unit = unit
+    )
+    .unwrap();
+
+    for (i, name) in table.names.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+
+        // 最小から最大までの帯
+        let mut band = Vec::new();
+        for (x, r) in points.iter() {
+            if let Some(v) = r.get(name, "Min") {
+                band.push(format!("{:.1},{:.1}", sx(*x), sy(v)));
+            }
+        }
+        for (x, r) in points.iter().rev() {
+            if let Some(v) = r.get(name, "Max") {
+                band.push(format!("{:.1},{:.1}", sx(*x), sy(v)));
+            }
+        }
+        if !band.is_empty() {
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}" fill-opacity="0.15" stroke="none"/>"#,
+                band.join(" "),
+                color
+            )
+            .unwrap();
+        }
+
+        // 平均の折れ線
+        let line: Vec<String> = points
+            .iter()
+            .filter_map(|(x, r)| {
+                r.get(name, "Avg")
+                    .map(|v| format!("{:.1},{:.1}", sx(*x), sy(v)))
+            })
+            .collect();
+        writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+            line.join(" "),
+            color
+        )
+        .unwrap();
+
+        // 凡例
+        let ly = MARGIN_TOP + 10.0 + 20.0 * i as f64;
+        let lx = MARGIN_LEFT + plot_w + 15.0;
+        writeln!(
+            svg,
+            r#"<line x1="{:.1}" y1="{ly:.1}" x2="{:.1}" y2="{ly:.1}" stroke="{}" stroke-width="2"/><text x="{:.1}" y="{:.1}">{}</text>"#,
+            lx,
+            lx + 20.0,
+            color,
+            lx + 26.0,
+            ly + 4.0,
+            xml_escape(name),
+            ly = ly
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// 目盛りの間隔として切りの良い値(1, 2, 5の10の累乗倍)を選ぶ
+fn nice_step(raw: f64) -> f64 {
+    let exp = raw.log10().floor();
+    let base = 10f64.powf(exp);
+    let frac = raw / base;
+    let nice = if frac <= 1.0 {
+        1.0
+    } else if frac <= 2.0 {
+        2.0
+    } else if frac <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * base
+}
+
+/// 縦軸の最大値に合った時間の単位と、秒からの倍率を選ぶ
+fn time_unit(max_secs: f64) -> (&'static str, f64) {
+    if max_secs >= 1.0 {
+        ("s", 1.0)
+    } else if max_secs >= 1e-3 {
+        ("ms", 1e3)
+    } else if max_secs >= 1e-6 {
+        ("µs", 1e6)
+    } else {
+        ("ns", 1e9)
+    }
+}
+
+/// 目盛りの数値を余計な0を付けずに書く
+fn format_tick(v: f64) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    s.to_string()
+}
+
+/// XMLのテキストとして使えるようにエスケープする
+pub fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `Digits`列の値から横軸の位置を求める
+///
+/// 数値でない行(分布名など)は`None`になる。
+fn label_x(label: &str) -> Option<f64> {
+    match label.strip_prefix("1e") {
+        Some(exp) => exp.parse::<i32>().ok().map(f64::from),
+        None => label.parse::<i32>().ok().map(|d| f64::from(d.abs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_svg() {
+        let table = ResultTable::parse(
+            "Digits\tSimpleAvg\tSimpleMin\tSimpleMax\tStdAvg\tStdMin\tStdMax\n\
+             1\t0.030\t0.029\t0.031\t0.031\t0.030\t0.033\n\
+             2\t0.031\t0.030\t0.033\t0.032\t0.031\t0.034\n",
+        )
+        .unwrap();
+        let svg = render_svg(&table, "u64 <test>");
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        assert!(svg.contains("u64 &lt;test&gt;"));
+        assert!(svg.contains("(ms)"));
+    }
+
+    #[test]
+    fn test_label_x() {
+        assert_eq!(label_x("3"), Some(3.0));
+        assert_eq!(label_x("-3"), Some(3.0));
+        assert_eq!(label_x("1e-5"), Some(-5.0));
+        assert_eq!(label_x("1e300"), Some(300.0));
+        assert_eq!(label_x("zipf"), None);
+    }
+
+    #[test]
+    fn test_nice_step() {
+        assert!((nice_step(0.7) - 1.0).abs() < 1e-12);
+        assert!((nice_step(0.0013) - 0.002).abs() < 1e-12);
+        assert!((nice_step(30.0) - 50.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{bail, Result};
+
+use crate::table::ResultTable;
+
+/// 比較の設定
+#[derive(Debug, Clone)]
+pub struct CompareConfig {
+    /// 平均が基準より何%遅くなったら性能低下とみなすか
+    pub threshold: f64,
+    /// 比較する実装の名前 (空の場合は両方にある全ての実装)
+    pub impls: Vec<String>,
+}
+
+/// ある桁数・実装での比較結果
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub label: String,
+    pub name: String,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl Change {
+    /// 速度の比 (1より大きければ速くなった)
+    pub fn speedup(&self) -> f64 {
+        self.baseline / self.current
+    }
+
+    /// 平均の変化率(%)
+    pub fn change_percent(&self) -> f64 {
+        (self.current / self.baseline - 1.0) * 100.0
+    }
+}
+
+/// 2つの結果の表の平均を、桁数・実装ごとに比較する
+///
+/// 片方にしかない行や実装は無視する。
+pub fn compare_tables(
+    baseline: &ResultTable,
+    current: &ResultTable,
+    config: &CompareConfig,
+) -> Vec<Change> {
+    let names: Vec<&String> = baseline
+        .names
+        .iter()
+        .filter(|n| current.names.contains(n))
+        .filter(|n| config.impls.is_empty() || config.impls.contains(n))
+        .collect();
+
+    let mut changes = Vec::new();
+    for base_row in baseline.rows.iter() {
+        let cur_row = match current.row(&base_row.label) {
+            Some(r) => r,
+            None => continue,
+        };
+        for name in names.iter() {
+            if let (Some(b), Some(c)) = (base_row.get(name, "Avg"), cur_row.get(name, "Avg")) {
+                changes.push(Change {
+                    label: base_row.label.clone(),
+                    name: name.to_string(),
+                    baseline: b,
+                    current: c,
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// 比較結果を標準出力に書き出し、性能低下があればエラーを返す
+pub fn print_changes(changes: &[Change], config: &CompareConfig) -> Result<()> {
+    if changes.is_empty() {
+        bail!("no common rows to compare");
+    }
+
+    println!("Digits\tImpl\tBaseline\tCurrent\tSpeedup\tChange\tStatus");
+    let mut regressions = 0;
+    for c in changes.iter() {
+        let change = c.change_percent();
+        let status = if change > config.threshold {
+            regressions += 1;
+            "regression"
+        } else if change < -config.threshold {
+            "improvement"
+        } else {
+            "ok"
+        };
+        println!(
+            "{}\t{}\t{:.6}\t{:.6}\t{:.3}\t{:+.2}%\t{}",
+            c.label,
+            c.name,
+            c.baseline,
+            c.current,
+            c.speedup(),
+            change,
+            status
+        );
+    }
+
+    if regressions > 0 {
+        bail!(
+            "{} of {} comparisons regressed by more than {}%",
+            regressions,
+            changes.len(),
+            config.threshold
+        );
+    }
+    eprintln!(
+        "No regressions beyond {}% in {} comparisons",
+        config.threshold,
+        changes.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_tables() {
+        let baseline =
+            ResultTable::parse("Digits\tSimpleAvg\tStdAvg\n1\t1.0\t1.0\n2\t1.0\t1.0\n").unwrap();
+        let current = ResultTable::parse("Digits\tSimpleAvg\tLutAvg\n2\t1.2\t0.5\n").unwrap();
+        let config = CompareConfig {
+            threshold: 5.0,
+            impls: Vec::new(),
+        };
+
+        let changes = compare_tables(&baseline, &current, &config);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].label, "2");
+        assert_eq!(changes[0].name, "Simple");
+        assert!((changes[0].change_percent() - 20.0).abs() < 1e-9);
+        assert!(print_changes(&changes, &config).is_err());
+
+        let lenient = CompareConfig {
+            threshold: 25.0,
+            impls: Vec::new(),
+        };
+        assert!(print_changes(&changes, &lenient).is_ok());
+    }
+}
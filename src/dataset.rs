@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+
+use crate::bench::BenchInteger;
+
+/// 入力ファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// 1行に1つの10進表記 (空行は無視する)
+    Text,
+    /// 8バイトのリトルエンディアンの`u64`の並び (符号付き整数型では`i64`の2の補数表現)
+    Binary,
+}
+
+impl FromStr for InputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(InputFormat::Text),
+            "binary" => Ok(InputFormat::Binary),
+            _ => bail!("unknown input format: {}", s),
+        }
+    }
+}
+
+/// 計測に使う値を読み込むファイル
+#[derive(Debug, Clone)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub format: InputFormat,
+}
+
+impl InputFile {
+    /// ファイルから値を読み込む
+    ///
+    /// `T`で表せない値があればエラーにする。
+    pub fn load<T: BenchInteger>(&self) -> Result<Vec<T>> {
+        let bytes = fs::read(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        let values = match self.format {
+            InputFormat::Text => parse_text(&bytes),
+            InputFormat::Binary => parse_binary(&bytes),
+        }
+        .with_context(|| format!("failed to parse {}", self.path.display()))?;
+
+        if values.is_empty() {
+            bail!("{} contains no values", self.path.display());
+        }
+        Ok(values)
+    }
+}
+
+/// 1行に1つの10進表記を読む
+fn parse_text<T: BenchInteger>(bytes: &[u8]) -> Result<Vec<T>> {
+    let text = std::str::from_utf8(bytes).context("not UTF-8")?;
+    let mut values = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(v) => values.push(v),
+            Err(_) => bail!("line {}: invalid value: {}", i + 1, line),
+        }
+    }
+    Ok(values)
+}
+
+/// 8バイトのリトルエンディアンの`u64`の並びを読む
+///
+/// 符号付き整数型では`i64`の2の補数表現として読む。
+fn parse_binary<T: BenchInteger>(bytes: &[u8]) -> Result<Vec<T>> {
+    if !bytes.len().is_multiple_of(8) {
+        bail!("length {} is not a multiple of 8", bytes.len());
+    }
+
+    let mut values = Vec::with_capacity(bytes.len() / 8);
+    for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+        let mut le = [0u8; 8];
+        le.copy_from_slice(chunk);
+        let v = u64::from_le_bytes(le);
+        // 符号付き整数型では`i64`の2の補数表現として読む
+        let (negative, m) = if T::SIGNED && (v as i64) < 0 {
+            (true, u128::from((v as i64).unsigned_abs()))
+        } else {
+            (false, u128::from(v))
+        };
+        let max = if negative {
+            T::MAX_MAGNITUDE
+        } else {
+            T::MAX_POSITIVE
+        };
+        if m > max {
+            let sign = if negative { "-" } else { "" };
+            bail!("value #{} ({}{}) does not fit in the type", i, sign, m);
+        }
+        values.push(T::from_parts(negative, m));
+    }
+    Ok(values)
+}
+
+/// 値を桁数ごとに分ける
+///
+/// 桁数の昇順に、負の値は`-3`のようなラベルで正の値の後に並べる。
+pub fn group_by_digits<T: BenchInteger>(values: &[T]) -> Vec<(String, Vec<T>)> {
+    let mut positive: Vec<Vec<T>> = vec![Vec::new(); T::MAX_LEN + 1];
+    let mut negative: Vec<Vec<T>> = vec![Vec::new(); T::MAX_LEN + 1];
+
+    let mut buf = T::new_buffer();
+    for v in values.iter().copied() {
+        let buf = buf.as_mut();
+        let digits = buf.len() - v.write_abs(buf);
+        if v.is_nonnegative() {
+            positive[digits].push(v);
+        } else {
+            negative[digits].push(v);
+        }
+    }
+
+    let positive = positive
+        .into_iter()
+        .enumerate()
+        .filter(|(_, vs)| !vs.is_empty())
+        .map(|(d, vs)| (d.to_string(), vs));
+    let negative = negative
+        .into_iter()
+        .enumerate()
+        .filter(|(_, vs)| !vs.is_empty())
+        .map(|(d, vs)| (format!("-{}", d), vs));
+    positive.chain(negative).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text() {
+        let vs: Vec<i64> = parse_text(b"1\n\n-20\n 300 \r\n").unwrap();
+        assert_eq!(vs, vec![1, -20, 300]);
+        assert!(parse_text::<u8>(b"256\n").is_err());
+        assert!(parse_text::<u64>(b"abc\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        let vs: Vec<u64> = parse_binary(&bytes).unwrap();
+        assert_eq!(vs, vec![7, u64::MAX]);
+        let vs: Vec<i64> = parse_binary(&bytes).unwrap();
+        assert_eq!(vs, vec![7, -1]);
+        let vs: Vec<i8> = parse_binary(&(-128i64).to_le_bytes()).unwrap();
+        assert_eq!(vs, vec![-128]);
+        assert!(parse_binary::<i8>(&(-129i64).to_le_bytes()).is_err());
+        assert!(parse_binary::<u8>(&bytes).is_err());
+        assert!(parse_binary::<u64>(&bytes[..5]).is_err());
+    }
+
+    #[test]
+    fn test_group_by_digits() {
+        let groups = group_by_digits(&[5i32, -7, 12, 0, -100, 34]);
+        let labels: Vec<&str> = groups.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels, vec!["1", "2", "-1", "-3"]);
+        assert_eq!(groups[0].1, vec![5, 0]);
+        assert_eq!(groups[1].1, vec![12, 34]);
+    }
+}
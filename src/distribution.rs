@@ -0,0 +1,244 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Error};
+use rand::distributions::Uniform;
+use rand::Rng;
+
+use crate::bench::BenchInteger;
+
+/// Zipf分布で生成するIDの最大値
+const ZIPF_MAX_ID: u128 = 10_000;
+/// Zipf分布の指数
+const ZIPF_EXPONENT: f64 = 1.0;
+
+/// ベンチマークの入力値の分布
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    /// 桁数ごとに、その桁数の範囲で一様 (桁数ごとに1行)
+    Digits,
+    /// 型の全範囲で一様
+    Uniform,
+    /// 絶対値の対数が一様
+    LogUniform,
+    /// 桁数は一様、先頭の数字はベンフォードの法則に従い、残りの数字は一様
+    Benford,
+    /// 1から`ZIPF_MAX_ID`までの小さなIDがZipf分布に従う
+    Zipf,
+    /// ランダムな値から1ずつ増えるカウンタ
+    Counter,
+}
+
+impl FromStr for Distribution {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(Distribution::Digits),
+            "uniform" => Ok(Distribution::Uniform),
+            "log-uniform" => Ok(Distribution::LogUniform),
+            "benford" => Ok(Distribution::Benford),
+            "zipf" => Ok(Distribution::Zipf),
+            "counter" => Ok(Distribution::Counter),
+            _ => bail!("unknown distribution: {}", s),
+        }
+    }
+}
+
+impl Distribution {
+    /// 結果の`Digits`列に出す名前
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Digits => "digits",
+            Distribution::Uniform => "uniform",
+            Distribution::LogUniform => "log-uniform",
+            Distribution::Benford => "benford",
+            Distribution::Zipf => "zipf",
+            Distribution::Counter => "counter",
+        }
+    }
+
+    /// 生成しうる値の範囲 (表示用)
+    pub fn range<T: BenchInteger>(self) -> (String, String) {
+        match self {
+            Distribution::Zipf => (
+                "1".to_string(),
+                ZIPF_MAX_ID.min(T::MAX_POSITIVE).to_string(),
+            ),
+            Distribution::Counter => ("0".to_string(), T::MAX_VALUE.to_string()),
+            _ => (T::MIN_VALUE.to_string(), T::MAX_VALUE.to_string()),
+        }
+    }
+
+    /// 分布に従って`size`個の値を生成する
+    ///
+    /// `Distribution::Digits`は桁数を決めないと生成できないので、ここでは扱わない。
+    pub fn generate<T: BenchInteger>(self, rng: &mut impl Rng, size: usize) -> Vec<T> {
+        match self {
+            Distribution::Digits => panic!("digits distribution needs a digit count"),
+            Distribution::Uniform => {
+                let dist = Uniform::new_inclusive(T::MIN_VALUE, T::MAX_VALUE);
+                (0..size).map(|_| rng.sample(&dist)).collect()
+            }
+            Distribution::LogUniform => (0..size)
+                .map(|_| {
+                    let negative = T::SIGNED && rng.gen::<bool>();
+                    let bound = magnitude_bound::<T>(negative);
+                    // [0, bound] に収まるよう (bound + 1)^u - 1 を使う
+                    let u: f64 = rng.gen();
+                    let m = ((bound as f64 + 1.0).powf(u) - 1.0) as u128;
+                    T::from_parts(negative, m.min(bound))
+                })
+                .collect(),
+            Distribution::Benford => (0..size)
+                .map(|_| {
+                    let negative = T::SIGNED && rng.gen::<bool>();
+                    T::from_parts(negative, benford(rng, magnitude_bound::<T>(negative)))
+                })
+                .collect(),
+            Distribution::Zipf => {
+                let max_id = ZIPF_MAX_ID.min(T::MAX_POSITIVE);
+                let cdf = zipf_cdf(max_id as usize);
+                (0..size)
+                    .map(|_| {
+                        let u: f64 = rng.gen();
+                        let rank = cdf.partition_point(|&c| c < u).min(cdf.len() - 1);
+                        T::from_parts(false, rank as u128 + 1)
+                    })
+                    .collect()
+            }
+            Distribution::Counter => {
+                let start = rng.gen_range(0, T::MAX_POSITIVE.saturating_add(1));
+                (0..size as u128)
+                    .map(|i| {
+                        // 型の最大値を超えたら0に戻る
+                        let m = match T::MAX_POSITIVE.checked_add(1) {
+                            Some(modulus) => (start + i % modulus) % modulus,
+                            None => start.wrapping_add(i),
+                        };
+                        T::from_parts(false, m)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// 符号に応じた絶対値の上限
+fn magnitude_bound<T: BenchInteger>(negative: bool) -> u128 {
+    if negative {
+        T::MAX_MAGNITUDE
+    } else {
+        T::MAX_POSITIVE
+    }
+}
+
+/// 先頭の数字がベンフォードの法則に従う`bound`以下の値を生成する
+///
+/// 0は生成しない。
+fn benford(rng: &mut impl Rng, bound: u128) -> u128 {
+    let max_digits = bound.to_string().len() as u32;
+    loop {
+        let digits = rng.gen_range(1, max_digits + 1);
+
+        // P(d) = log10(1 + 1/d)
+        let u: f64 = rng.gen();
+        let mut acc = 0.0;
+        let mut leading = 9;
+        for d in 1..=9u128 {
+            acc += (1.0 + 1.0 / d as f64).log10();
+            if u < acc {
+                leading = d;
+                break;
+            }
+        }
+
+        let scale = 10u128.pow(digits - 1);
+        let rest = if scale > 1 {
+            rng.gen_range(0, scale)
+        } else {
+            0
+        };
+        let m = leading
+            .checked_mul(scale)
+            .and_then(|top| top.checked_add(rest));
+        if let Some(m) = m {
+            if m <= bound {
+                return m;
+            }
+        }
+    }
+}
+
+/// 1から`n`までの順位のZipf分布の累積分布
+fn zipf_cdf(n: usize) -> Vec<f64> {
+    let weights: Vec<f64> = (1..=n)
+        .map(|k| 1.0 / (k as f64).powf(ZIPF_EXPONENT))
+        .collect();
+    let total: f64 = weights.iter().sum();
+
+    let mut acc = 0.0;
+    weights
+        .iter()
+        .map(|w| {
+            acc += w / total;
+            acc
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_generate_in_range() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        for dist in [
+            Distribution::Uniform,
+            Distribution::LogUniform,
+            Distribution::Benford,
+            Distribution::Zipf,
+            Distribution::Counter,
+        ]
+        .iter()
+        {
+            assert_eq!(dist.generate::<u8>(&mut rng, 1000).len(), 1000);
+            assert_eq!(dist.generate::<i128>(&mut rng, 1000).len(), 1000);
+            let vs = dist.generate::<i8>(&mut rng, 1000);
+            if *dist == Distribution::Zipf || *dist == Distribution::Counter {
+                assert!(vs.iter().all(|&v| v >= 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_counter_increments() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let vs = Distribution::Counter.generate::<u64>(&mut rng, 100);
+        assert!(vs.windows(2).all(|w| w[1] == w[0].wrapping_add(1)));
+
+        let vs = Distribution::Counter.generate::<u8>(&mut rng, 1000);
+        assert!(vs.windows(2).all(|w| w[1] == w[0].wrapping_add(1)));
+    }
+
+    #[test]
+    fn test_benford_leading_digit() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let vs = Distribution::Benford.generate::<u64>(&mut rng, 10000);
+        let ones = vs.iter().filter(|v| v.to_string().starts_with('1')).count();
+        // log10(2) ≒ 30.1%
+        assert!(2700 < ones && ones < 3300, "{}", ones);
+    }
+
+    #[test]
+    fn test_zipf_small_ids() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        let vs = Distribution::Zipf.generate::<u32>(&mut rng, 10000);
+        assert!(vs.iter().all(|&v| 1 <= v && v as u128 <= ZIPF_MAX_ID));
+        let ones = vs.iter().filter(|&&v| v == 1).count();
+        // 1 / H(10000) ≒ 10.2%
+        assert!(800 < ones && ones < 1250, "{}", ones);
+    }
+}
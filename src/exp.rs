@@ -0,0 +1,213 @@
+use std::fmt::{self, Alignment, Write};
+use std::str::from_utf8_unchecked;
+
+use crate::integer::FormatInteger;
+use crate::wide::U128_MAX_LEN;
+
+/// 仮数部の末尾の0埋めをこの長さずつ書き出す
+const ZEROS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// `v`を`1.234e3`の形式で書式化する
+///
+/// 精度の指定がなければ仮数部の末尾の0を省き、指定があればその桁数に偶数丸めするか0で埋める。
+pub(crate) fn fmt_exp<T: FormatInteger>(
+    v: T,
+    f: &mut fmt::Formatter<'_>,
+    upper: bool,
+) -> fmt::Result {
+    let mut buf = T::new_buffer();
+    let buf = buf.as_mut();
+    let cur = v.write_abs(buf);
+    let digits = &mut buf[cur..];
+    let mut exp = digits.len() - 1;
+
+    // 仮数部に使う桁数と、その後に続ける0の数
+    let (len, zeros) = match f.precision() {
+        None => {
+            let mut len = digits.len();
+            while len > 1 && digits[len - 1] == b'0' {
+                len -= 1;
+            }
+            (len, 0)
+        }
+        Some(p) if p < digits.len() - 1 => {
+            let len = p + 1;
+            if round_up(digits[len - 1], &digits[len..]) && carry(&mut digits[..len]) {
+                exp += 1;
+            }
+            (len, 0)
+        }
+        Some(p) => (digits.len(), p - (digits.len() - 1)),
+    };
+
+    // 仮数部の有効桁と`.`だけを組み立てる
+    let mut mantissa = [0u8; U128_MAX_LEN + 1];
+    mantissa[0] = digits[0];
+    let mut mantissa_len = 1;
+    if len > 1 || zeros > 0 {
+        mantissa[1] = b'.';
+        mantissa[2..len + 1].copy_from_slice(&digits[1..len]);
+        mantissa_len = len + 1;
+    }
+    let mantissa = &mantissa[..mantissa_len];
+
+    let exp_len = if exp >= 10 { 2 } else { 1 };
+    let mut exponent = [0u8; 3];
+    let exponent = &mut exponent[..1 + exp_len];
+    exponent[0] = if upper { b'E' } else { b'e' };
+    (exp as u8).write_abs(&mut exponent[1..]);
+
+    let sign = if !v.is_nonnegative() {
+        "-"
+    } else if f.sign_plus() {
+        "+"
+    } else {
+        ""
+    };
+    let total = (sign.len() + mantissa.len() + exponent.len()).saturating_add(zeros);
+    let pad = f.width().unwrap_or(0).saturating_sub(total);
+
+    // 0埋めは桁数によらずメモリを確保しないよう、幅の埋めも含めて直接書き出す
+    let (fill, pre, post) = if f.sign_aware_zero_pad() {
+        f.write_str(sign)?;
+        ('0', pad, 0)
+    } else {
+        match f.align() {
+            Some(Alignment::Left) => (f.fill(), 0, pad),
+            Some(Alignment::Center) => (f.fill(), pad / 2, pad.div_ceil(2)),
+            Some(Alignment::Right) | None => (f.fill(), pad, 0),
+        }
+    };
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    if !f.sign_aware_zero_pad() {
+        f.write_str(sign)?;
+    }
+    unsafe {
+        // 数字と`.`、`e`だけなのでASCII
+        f.write_str(from_utf8_unchecked(mantissa))?;
+        write_zeros(f, zeros)?;
+        f.write_str(from_utf8_unchecked(exponent))?;
+    }
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// `n`個の`0`を`ZEROS`ずつ書き出す
+fn write_zeros(f: &mut fmt::Formatter<'_>, mut n: usize) -> fmt::Result {
+    while n > 0 {
+        let chunk = n.min(ZEROS.len());
+        f.write_str(&ZEROS[..chunk])?;
+        n -= chunk;
+    }
+    Ok(())
+}
+
+/// 最後に残す桁`last`と切り捨てる桁`rest`から、切り上げるかどうかを偶数丸めで決める
+fn round_up(last: u8, rest: &[u8]) -> bool {
+    match rest[0].cmp(&b'5') {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => rest[1..].iter().any(|&d| d != b'0') || (last - b'0') % 2 == 1,
+    }
+}
+
+/// 数字列`digits`に1を足す
+///
+/// 全ての桁が9だった場合は`1000...`にして`true`を返す(指数を1つ上げる必要がある)。
+fn carry(digits: &mut [u8]) -> bool {
+    for d in digits.iter_mut().rev() {
+        if *d == b'9' {
+            *d = b'0';
+        } else {
+            *d += 1;
+            return false;
+        }
+    }
+    digits[0] = b'1';
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SimpleDisplay;
+
+    #[test]
+    fn test_exp() {
+        let vals: [u64; 14] = [
+            0,
+            1,
+            10,
+            15,
+            25,
+            95,
+            125,
+            135,
+            1200,
+            1250,
+            1251,
+            9999,
+            99950,
+            u64::MAX,
+        ];
+        for val in vals.iter().copied() {
+            assert_eq!(format!("{:e}", SimpleDisplay(val)), format!("{:e}", val));
+            assert_eq!(format!("{:E}", SimpleDisplay(val)), format!("{:E}", val));
+            for p in 0..6 {
+                assert_eq!(
+                    format!("{:.*e}", p, SimpleDisplay(val)),
+                    format!("{:.*e}", p, val)
+                );
+            }
+        }
+
+        assert_eq!(format!("{:010.1e}", SimpleDisplay(-1250i64)), "-00001.2e3");
+        assert_eq!(
+            format!("{:e}", SimpleDisplay(i128::MIN)),
+            format!("{:e}", i128::MIN)
+        );
+        assert_eq!(
+            format!("{:.80e}", SimpleDisplay(7u8)),
+            format!("{:.80e}", 7u8)
+        );
+    }
+
+    #[test]
+    fn test_exp_padding() {
+        for val in [0i64, 7, -1250, i64::MIN].iter().copied() {
+            assert_eq!(
+                format!("{:+012.3e}", SimpleDisplay(val)),
+                format!("{:+012.3e}", val)
+            );
+            assert_eq!(
+                format!("{:*^15.200e}", SimpleDisplay(val)),
+                format!("{:*^15.200e}", val)
+            );
+            assert_eq!(
+                format!("{:<300.200E}", SimpleDisplay(val)),
+                format!("{:<300.200E}", val)
+            );
+            assert_eq!(
+                format!("{:>9e}", SimpleDisplay(val)),
+                format!("{:>9e}", val)
+            );
+        }
+    }
+
+    #[test]
+    fn test_exp_max_precision() {
+        // 書式の引数で指定できる最大の精度と幅でも、std と同じ出力になる
+        let p = usize::from(u16::MAX);
+        assert_eq!(
+            format!("{:.*e}", p, SimpleDisplay(7u8)),
+            format!("{:.*e}", p, 7u8)
+        );
+        assert_eq!(
+            format!("{:1$.2$e}", SimpleDisplay(-5i32), p, p - 10),
+            format!("{:1$.2$e}", -5i32, p, p - 10)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::{self, Alignment, Display, Write};
+use std::str::from_utf8_unchecked;
+
+use crate::integer::FormatInteger;
+use crate::ryu::{d2d, f2d};
+
+/// 浮動小数点数の小数表記の最大長 (`f64`の最小の非正規化数の場合)
+///
+/// 符号は含まない。
+pub const FLOAT_MAX_LEN: usize = 326;
+
+/// 浮動小数点数の値の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatKind {
+    Nan,
+    Infinite,
+    Zero,
+    /// 有限で0でない値。値は`仮数 * 10^指数`で、仮数は元の値に戻る最短の桁数
+    Finite(u64, i32),
+}
+
+/// 最短表記で書式化できる浮動小数点数型
+pub trait FormatFloat: Copy + Display {
+    /// 符号ビットが立っているかどうか (`-0.0`や負のNaNも含む)
+    fn is_sign_negative(self) -> bool;
+
+    /// 値の種類と、有限の場合は最短の10進表記を求める
+    fn decode(self) -> FloatKind;
+}
+
+macro_rules! impl_format_float {
+    ($($t:ty, $mantissa_bits:expr, $exponent_mask:expr, $d2d:ident;)*) => {$(
+        impl FormatFloat for $t {
+            fn is_sign_negative(self) -> bool {
+                <$t>::is_sign_negative(self)
+            }
+
+            fn decode(self) -> FloatKind {
+                let bits = self.to_bits();
+                let mantissa = bits & ((1 << $mantissa_bits) - 1);
+                let exponent = (bits >> $mantissa_bits) as u32 & $exponent_mask;
+
+                match (exponent, mantissa) {
+                    ($exponent_mask, 0) => FloatKind::Infinite,
+                    ($exponent_mask, _) => FloatKind::Nan,
+                    (0, 0) => FloatKind::Zero,
+                    _ => {
+                        let (digits, exp) = $d2d(mantissa, exponent);
+                        FloatKind::Finite(u64::from(digits), exp)
+                    }
+                }
+            }
+        }
+    )*};
+}
+
+impl_format_float! {
+    f32, 23, 0xff, f2d;
+    f64, 52, 0x7ff, d2d;
+}
+
+/// 最短表記の浮動小数点数のラッパー型
+///
+/// Ryuアルゴリズムで元の値に戻る最短の桁を求め、標準ライブラリの`{}`と同じく指数を使わない
+/// 小数表記で書く。精度(`{:.3}`など)が指定された場合は標準ライブラリに任せる。
+#[derive(Debug, Clone, Copy)]
+pub struct FloatDisplay<F>(pub F);
+
+impl<F: FormatFloat> Display for FloatDisplay<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.precision().is_some() {
+            return Display::fmt(&self.0, f);
+        }
+
+        let mut buf = [0u8; FLOAT_MAX_LEN];
+        let len = match self.0.decode() {
+            // NaN は符号を付けないので pad_integral を使えない
+            FloatKind::Nan => return pad_nan(f),
+            FloatKind::Infinite => {
+                buf[..3].copy_from_slice(b"inf");
+                3
+            }
+            FloatKind::Zero => {
+                buf[0] = b'0';
+                1
+            }
+            FloatKind::Finite(digits, exp) => write_decimal(digits, exp, &mut buf),
+        };
+
+        unsafe {
+            let buf_slice = from_utf8_unchecked(&buf[..len]);
+            f.pad_integral(!self.0.is_sign_negative(), "", buf_slice)
+        }
+    }
+}
+
+/// `digits * 10^exp`を指数を使わない小数表記で`buf`の先頭に書き込み、長さを返す
+fn write_decimal(digits: u64, exp: i32, buf: &mut [u8]) -> usize {
+    let mut tmp = u64::new_buffer();
+    let cur = digits.write_abs(&mut tmp);
+    let digits = &tmp[cur..];
+    let n = digits.len();
+
+    if exp >= 0 {
+        // 整数: 数字列の後に0を並べる
+        let end = n + exp as usize;
+        buf[..n].copy_from_slice(digits);
+        buf[n..end].fill(b'0');
+        end
+    } else if n as i32 + exp > 0 {
+        // 数字列の途中に小数点を入れる
+        let point = (n as i32 + exp) as usize;
+        buf[..point].copy_from_slice(&digits[..point]);
+        buf[point] = b'.';
+        buf[point + 1..n + 1].copy_from_slice(&digits[point..]);
+        n + 1
+    } else {
+        // 0.000ddd の形
+        let zeros = (-exp) as usize - n;
+        buf[..2].copy_from_slice(b"0.");
+        buf[2..2 + zeros].fill(b'0');
+        buf[2 + zeros..2 + zeros + n].copy_from_slice(digits);
+        2 + zeros + n
+    }
+}
+
+/// 符号を付けずに`NaN`を書き、幅の指定があれば詰める
+///
+/// 標準ライブラリと同じく、`0`フラグの場合は`00NaN`のように0で詰める。
+fn pad_nan(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    const NAN: &str = "NaN";
+    let pad = f.width().unwrap_or(0).saturating_sub(NAN.len());
+    if pad == 0 {
+        return f.write_str(NAN);
+    }
+
+    let (fill, pre, post) = if f.sign_aware_zero_pad() {
+        ('0', pad, 0)
+    } else {
+        match f.align() {
+            Some(Alignment::Left) => (f.fill(), 0, pad),
+            Some(Alignment::Center) => (f.fill(), pad / 2, pad.div_ceil(2)),
+            Some(Alignment::Right) | None => (f.fill(), pad, 0),
+        }
+    };
+    for _ in 0..pre {
+        f.write_char(fill)?;
+    }
+    f.write_str(NAN)?;
+    for _ in 0..post {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    fn check<F: FormatFloat>(v: F) {
+        assert_eq!(format!("{}", FloatDisplay(v)), format!("{}", v));
+    }
+
+    #[test]
+    fn test_float_display_edges() {
+        let vals = [
+            0.0,
+            -0.0,
+            1.0,
+            -1.5,
+            0.1,
+            0.3,
+            1e21,
+            1e-7,
+            123456.789,
+            f64::MAX,
+            f64::MIN,
+            f64::MIN_POSITIVE,
+            f64::EPSILON,
+            5e-324,
+            2.225073858507201e-308,
+            9007199254740993.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            -f64::NAN,
+        ];
+        for v in vals.iter().copied() {
+            check(v);
+            check(v as f32);
+        }
+        let vals = [
+            f32::MAX,
+            f32::MIN_POSITIVE,
+            1e-45,
+            1.1754942e-38,
+            16777217.0,
+            3.4e38,
+        ];
+        for v in vals.iter().copied() {
+            check(v);
+        }
+    }
+
+    #[test]
+    fn test_float_display_flags() {
+        for &v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0, 1.5].iter() {
+            assert_eq!(format!("{:08}", FloatDisplay(v)), format!("{:08}", v));
+            assert_eq!(format!("{:+}", FloatDisplay(v)), format!("{:+}", v));
+            assert_eq!(format!("{:*^9}", FloatDisplay(v)), format!("{:*^9}", v));
+            assert_eq!(format!("{:<6}", FloatDisplay(v)), format!("{:<6}", v));
+            assert_eq!(format!("{:.2}", FloatDisplay(v)), format!("{:.2}", v));
+        }
+    }
+
+    #[test]
+    fn test_float_display_random() {
+        let mut rng = XorShiftRng::seed_from_u64(0x5eed);
+        for _ in 0..100_000 {
+            check(f64::from_bits(rng.gen()));
+            check(f32::from_bits(rng.gen()));
+        }
+        // 値の小さい整数と短い小数
+        for i in 0..10_000 {
+            check(i as f64 / 100.0);
+            check(i as f32 / 1000.0);
+        }
+    }
+}
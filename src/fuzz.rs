@@ -0,0 +1,313 @@
+//! 書式指定を含めて標準ライブラリと出力を比べる差分ファジング用の補助
+
+use std::fmt::{self, Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
+
+use crate::integer::FormatInteger;
+use crate::lut::LutDisplay;
+use crate::simple::SimpleDisplay;
+
+/// 使える埋め文字 (書式文字列はリテラルでなければならないため固定)
+pub const FILLS: [char; 3] = [' ', '*', 'あ'];
+
+/// 書式の型 (`{:x}`の`x`など、`Display`は空文字列)
+pub const TYPES: [&str; 7] = ["", "x", "X", "o", "b", "e", "E"];
+
+/// 揃え方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// 全ての書式トレイトを実装した型
+pub trait Formattable:
+    Display + LowerHex + UpperHex + Octal + Binary + LowerExp + UpperExp
+{
+}
+
+impl<T> Formattable for T where
+    T: Display + LowerHex + UpperHex + Octal + Binary + LowerExp + UpperExp
+{
+}
+
+/// 実行時に組み立てる書式指定
+///
+/// `{:*^+#08.3x}`のような書式文字列の各要素に対応する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSpec {
+    /// 埋め文字 (`FILLS`のいずれか、`align`が`None`なら無視される)
+    pub fill: char,
+    pub align: Option<Align>,
+    /// `+`
+    pub plus: bool,
+    /// `#`
+    pub alternate: bool,
+    /// `0`
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+    /// 書式の型 (`TYPES`のいずれか)
+    pub ty: &'static str,
+}
+
+impl FormatSpec {
+    /// 3バイトから書式指定を作る
+    ///
+    /// 1バイト目の各ビットで揃え方、埋め文字、`+`、`#`、`0`、幅の有無を、
+    /// 2バイト目で幅(0〜63)を、3バイト目で書式トレイトと精度(0〜15)の有無と値を決める。
+    pub fn from_bytes(b: [u8; 3]) -> FormatSpec {
+        let align = match b[0] & 3 {
+            0 => None,
+            1 => Some(Align::Left),
+            2 => Some(Align::Center),
+            _ => Some(Align::Right),
+        };
+        FormatSpec {
+            fill: FILLS[usize::from(b[0] >> 2 & 3) % FILLS.len()],
+            align,
+            plus: b[0] & 0x10 != 0,
+            alternate: b[0] & 0x20 != 0,
+            zero: b[0] & 0x40 != 0,
+            width: if b[0] & 0x80 != 0 {
+                Some(usize::from(b[1] % 64))
+            } else {
+                None
+            },
+            precision: if b[2] & 0x08 != 0 {
+                Some(usize::from(b[2] >> 4))
+            } else {
+                None
+            },
+            ty: TYPES[usize::from(b[2] & 7) % TYPES.len()],
+        }
+    }
+}
+
+impl Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{:")?;
+        if let Some(align) = self.align {
+            let c = match align {
+                Align::Left => '<',
+                Align::Center => '^',
+                Align::Right => '>',
+            };
+            write!(f, "{}{}", self.fill, c)?;
+        }
+        if self.plus {
+            f.write_str("+")?;
+        }
+        if self.alternate {
+            f.write_str("#")?;
+        }
+        if self.zero {
+            f.write_str("0")?;
+        }
+        if let Some(w) = self.width {
+            write!(f, "{}", w)?;
+        }
+        if let Some(p) = self.precision {
+            write!(f, ".{}", p)?;
+        }
+        write!(f, "{}}}", self.ty)
+    }
+}
+
+/// 書式指定の残りの要素を順にリテラルとして積み上げて`format!`を呼ぶ
+macro_rules! format_spec {
+    (@sign $s:expr, $v:expr, $($p:literal)*) => {
+        if $s.plus {
+            format_spec!(@alternate $s, $v, $($p)* "+")
+        } else {
+            format_spec!(@alternate $s, $v, $($p)*)
+        }
+    };
+    (@alternate $s:expr, $v:expr, $($p:literal)*) => {
+        if $s.alternate {
+            format_spec!(@zero $s, $v, $($p)* "#")
+        } else {
+            format_spec!(@zero $s, $v, $($p)*)
+        }
+    };
+    (@zero $s:expr, $v:expr, $($p:literal)*) => {
+        if $s.zero {
+            format_spec!(@trait $s, $v, $($p)* "0")
+        } else {
+            format_spec!(@trait $s, $v, $($p)*)
+        }
+    };
+    (@trait $s:expr, $v:expr, $($p:literal)*) => {
+        match $s.ty {
+            "" => format_spec!(@width $s, $v, [$($p)*] ""),
+            "x" => format_spec!(@width $s, $v, [$($p)*] "x"),
+            "X" => format_spec!(@width $s, $v, [$($p)*] "X"),
+            "o" => format_spec!(@width $s, $v, [$($p)*] "o"),
+            "b" => format_spec!(@width $s, $v, [$($p)*] "b"),
+            "e" => format_spec!(@width $s, $v, [$($p)*] "e"),
+            "E" => format_spec!(@width $s, $v, [$($p)*] "E"),
+            ty => panic!("unsupported type: {:?}", ty),
+        }
+    };
+    (@width $s:expr, $v:expr, [$($p:literal)*] $ty:literal) => {
+        match ($s.width, $s.precision) {
+            (Some(w), Some(p)) => format!(concat!("{:", $($p,)* "w$.p$", $ty, "}"), $v, w = w, p = p),
+            (Some(w), None) => format!(concat!("{:", $($p,)* "w$", $ty, "}"), $v, w = w),
+            (None, Some(p)) => format!(concat!("{:", $($p,)* ".p$", $ty, "}"), $v, p = p),
+            (None, None) => format!(concat!("{:", $($p,)* $ty, "}"), $v),
+        }
+    };
+}
+
+/// `spec`の書式で`v`を文字列にする
+///
+/// `spec.fill`が`FILLS`に、`spec.ty`が`TYPES`にない場合はパニックする。
+pub fn format_with(spec: &FormatSpec, v: &dyn Formattable) -> String {
+    match (spec.align, spec.fill) {
+        (None, _) => format_spec!(@sign spec, v,),
+        (Some(Align::Left), ' ') => format_spec!(@sign spec, v, "<"),
+        (Some(Align::Left), '*') => format_spec!(@sign spec, v, "*<"),
+        (Some(Align::Left), 'あ') => format_spec!(@sign spec, v, "あ<"),
+        (Some(Align::Center), ' ') => format_spec!(@sign spec, v, "^"),
+        (Some(Align::Center), '*') => format_spec!(@sign spec, v, "*^"),
+        (Some(Align::Center), 'あ') => format_spec!(@sign spec, v, "あ^"),
+        (Some(Align::Right), ' ') => format_spec!(@sign spec, v, ">"),
+        (Some(Align::Right), '*') => format_spec!(@sign spec, v, "*>"),
+        (Some(Align::Right), 'あ') => format_spec!(@sign spec, v, "あ>"),
+        (Some(_), fill) => panic!("unsupported fill: {:?}", fill),
+    }
+}
+
+/// `Display`だけを実装した型を`format_with`に渡すためのラッパー
+///
+/// `Display`以外の書式トレイトで書式化するとパニックする。
+struct DisplayOnly<D>(D);
+
+macro_rules! impl_display_only {
+    ($($tr:ident)*) => {$(
+        impl<D: Display> $tr for DisplayOnly<D> {
+            fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+                unreachable!(concat!(stringify!($tr), " is not implemented"))
+            }
+        }
+    )*};
+}
+
+impl_display_only!(LowerHex UpperHex Octal Binary LowerExp UpperExp);
+
+impl<D: Display> Display for DisplayOnly<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// `spec`の書式で`v`を各実装で文字列にし、標準ライブラリと比べる
+///
+/// `LutDisplay`は`Display`しか実装していないので、その場合だけ比べる。
+pub fn check_value<T: FormatInteger>(spec: &FormatSpec, v: T) -> Result<(), String> {
+    let expected = format_with(spec, &v);
+    let mut actuals = vec![("Simple", format_with(spec, &SimpleDisplay(v)))];
+    if spec.ty.is_empty() {
+        actuals.push(("Lut", format_with(spec, &DisplayOnly(LutDisplay(v)))));
+    }
+    for (name, actual) in actuals.iter() {
+        if *actual != expected {
+            return Err(format!(
+                "{} differs from std for {} with {}: expected {:?}, got {:?}",
+                name, v, spec, expected, actual
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// ファジングの入力から型、書式指定、値を取り出して`check_value`で検査する
+///
+/// 1バイト目で型を、続く3バイトで書式指定を、残り(最大16バイト)をリトルエンディアンの値として
+/// 型の幅に切り詰める。4バイトに満たない入力は何もしない。
+pub fn check_bytes(data: &[u8]) -> Result<(), String> {
+    if data.len() < 4 {
+        return Ok(());
+    }
+    let spec = FormatSpec::from_bytes([data[1], data[2], data[3]]);
+    let mut le = [0u8; 16];
+    let rest = &data[4..data.len().min(20)];
+    le[..rest.len()].copy_from_slice(rest);
+    let raw = u128::from_le_bytes(le);
+
+    match data[0] % 12 {
+        0 => check_value(&spec, raw as u8),
+        1 => check_value(&spec, raw as u16),
+        2 => check_value(&spec, raw as u32),
+        3 => check_value(&spec, raw as u64),
+        4 => check_value(&spec, raw as usize),
+        5 => check_value(&spec, raw),
+        6 => check_value(&spec, raw as i8),
+        7 => check_value(&spec, raw as i16),
+        8 => check_value(&spec, raw as i32),
+        9 => check_value(&spec, raw as i64),
+        10 => check_value(&spec, raw as isize),
+        _ => check_value(&spec, raw as i128),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn test_format_with() {
+        let spec = FormatSpec {
+            fill: 'あ',
+            align: Some(Align::Center),
+            plus: true,
+            alternate: false,
+            zero: false,
+            width: Some(6),
+            precision: None,
+            ty: "",
+        };
+        assert_eq!(spec.to_string(), "{:あ^+6}");
+        assert_eq!(format_with(&spec, &42), "あ+42ああ");
+
+        let spec = FormatSpec::from_bytes([0xc0, 6, 0]);
+        assert_eq!(spec.to_string(), "{:06}");
+        assert_eq!(format_with(&spec, &-42), "-00042");
+
+        let spec = FormatSpec::from_bytes([0xe0, 10, 0x1d]);
+        assert_eq!(spec.to_string(), "{:#010.1e}");
+        assert_eq!(format_with(&spec, &-1250), "-00001.2e3");
+    }
+
+    #[test]
+    fn test_check_bytes_all_flags() {
+        let values: [u128; 4] = [0, 9, 0x80, u128::MAX];
+        for ty in 0..12u8 {
+            for flags in 0..=255u8 {
+                for width in [0u8, 5, 40].iter().copied() {
+                    // 書式トレイト7種類それぞれについて、精度なしと精度2
+                    for extra in (0..7u8).chain(0x28..0x2f) {
+                        for v in values.iter() {
+                            let mut data = vec![ty, flags, width, extra];
+                            data.extend_from_slice(&v.to_le_bytes());
+                            check_bytes(&data).unwrap();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_bytes_random() {
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        for _ in 0..100_000 {
+            let len = rng.gen_range(0, 21);
+            let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            check_bytes(&data).unwrap();
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use std::fmt::{Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
+use std::mem::MaybeUninit;
+
+use crate::len::decimal_len;
+use crate::lut::DEC_DIGITS_LUT;
+use crate::wide::{split_u128, write_digits_u128_into, CHUNK_LEN};
+
+mod private {
+    /// クレートの外から`FormatInteger`を実装できないようにする
+    pub trait Sealed {}
+
+    macro_rules! impl_sealed {
+        ($($t:ty),*) => {$(
+            impl Sealed for $t {}
+        )*};
+    }
+
+    impl_sealed!(u8, u16, u32, u64, usize, u128, i8, i16, i32, i64, isize, i128);
+}
+
+/// 素朴なitoa実装で扱える整数型
+///
+/// 型ごとの幅に合った除算とバッファで10進表記を書き込む。
+/// 比較のため、標準ライブラリの書式トレイトを全て実装していることを要求する。
+///
+/// 書き込んだ内容はASCIIであるものとして検査せずに`&str`にするので、
+/// プリミティブ整数型以外には実装できない(封印されている)。
+pub trait FormatInteger:
+    private::Sealed + Copy + Display + LowerHex + UpperHex + Octal + Binary + LowerExp + UpperExp
+{
+    /// 符号を除いた10進表記の最大桁数
+    const MAX_LEN: usize;
+
+    /// ビット幅
+    const BITS: u32;
+
+    /// `MAX_LEN`バイトのバッファ型
+    type Buffer: AsRef<[u8]> + AsMut<[u8]>;
+
+    /// 0で初期化されたバッファを作る
+    fn new_buffer() -> Self::Buffer;
+
+    /// 符号を含めた10進表記が収まるバッファ型
+    type StrBuffer: AsRef<[u8]> + AsMut<[u8]>;
+
+    /// 0で初期化された`StrBuffer`を作る
+    fn new_str_buffer() -> Self::StrBuffer;
+
+    /// 値が0以上かどうか
+    fn is_nonnegative(self) -> bool;
+
+    /// 絶対値の10進表記を`buf`の末尾に詰めて書き込み、先頭の位置を返す
+    ///
+    /// `buf`は絶対値の桁数以上の長さが必要。
+    fn write_abs(self, buf: &mut [u8]) -> usize;
+
+    /// `write_abs`と同じ内容を、2桁ずつ表引きして書き込む
+    fn write_abs_lut(self, buf: &mut [u8]) -> usize;
+
+    /// 絶対値の10進表記の桁数
+    fn abs_len(self) -> usize;
+
+    /// 絶対値の10進表記を、未初期化の`buf`全体にちょうど収まるように書き込む
+    ///
+    /// `buf`が桁数より長い場合は先頭を`0`で埋めるので、`buf`の全ての要素が初期化される。
+    fn write_abs_exact(self, buf: &mut [MaybeUninit<u8>]);
+
+    /// 2の補数表現の`2^shift`進表記を`buf`の末尾に詰めて書き込み、先頭の位置を返す
+    ///
+    /// 各桁の文字は`digits`から引く。`buf`は`MAX_BITS_LEN`バイトあれば足りる。
+    fn write_bits(self, buf: &mut [u8], shift: u32, digits: &[u8; 16]) -> usize;
+
+    /// 絶対値の`radix`進表記を`buf`の末尾に詰めて書き込み、先頭の位置を返す
+    ///
+    /// 各桁の文字は`digits`から引く。`radix`は2以上64以下で、`digits.len()`以下でなければならない。
+    fn write_abs_radix(self, buf: &mut [u8], radix: u32, digits: &[u8]) -> usize;
+}
+
+/// 2進表記の最大桁数 (`u128`の場合)
+pub const MAX_BITS_LEN: usize = 128;
+
+/// 符号なし整数型の`write_bits`の本体
+macro_rules! write_bits_body {
+    ($t:ty, $self:expr, $buf:expr, $shift:expr, $digits:expr) => {{
+        let mask: $t = (1 << $shift) - 1;
+        let mut n = $self;
+        let mut cur = $buf.len();
+
+        while {
+            cur -= 1;
+            $buf[cur] = $digits[(n & mask) as usize];
+            n >>= $shift;
+
+            n > 0
+        } {}
+
+        cur
+    }};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty, $len:expr;)*) => {$(
+        impl FormatInteger for $t {
+            const MAX_LEN: usize = $len;
+
+            const BITS: u32 = <$t>::BITS;
+
+            type Buffer = [u8; $len];
+
+            fn new_buffer() -> Self::Buffer {
+                [0; $len]
+            }
+
+            type StrBuffer = [u8; $len];
+
+            fn new_str_buffer() -> Self::StrBuffer {
+                [0; $len]
+            }
+
+            fn is_nonnegative(self) -> bool {
+                true
+            }
+
+            fn write_abs(self, buf: &mut [u8]) -> usize {
+                let mut n = self;
+                let mut cur = buf.len();
+
+                while {
+                    // do-while と等価なイディオム
+                    cur -= 1;
+                    let m = n % 10;
+                    n /= 10;
+                    buf[cur] = (m as u8) + b'0';
+
+                    n > 0
+                } {}
+
+                cur
+            }
+
+            fn write_abs_lut(self, buf: &mut [u8]) -> usize {
+                let mut n = self;
+                let mut cur = buf.len();
+
+                while n >= 100 {
+                    let d = (n % 100) as usize * 2;
+                    n /= 100;
+                    cur -= 2;
+                    buf[cur..cur + 2].copy_from_slice(&DEC_DIGITS_LUT[d..d + 2]);
+                }
+
+                if n >= 10 {
+                    let d = n as usize * 2;
+                    cur -= 2;
+                    buf[cur..cur + 2].copy_from_slice(&DEC_DIGITS_LUT[d..d + 2]);
+                } else {
+                    cur -= 1;
+                    buf[cur] = (n as u8) + b'0';
+                }
+
+                cur
+            }
+
+            fn abs_len(self) -> usize {
+                decimal_len(self as u64)
+            }
+
+            fn write_abs_exact(self, buf: &mut [MaybeUninit<u8>]) {
+                let mut n = self;
+                for slot in buf.iter_mut().rev() {
+                    slot.write((n % 10) as u8 + b'0');
+                    n /= 10;
+                }
+            }
+
+            fn write_bits(self, buf: &mut [u8], shift: u32, digits: &[u8; 16]) -> usize {
+                write_bits_body!($t, self, buf, shift, digits)
+            }
+
+            fn write_abs_radix(self, buf: &mut [u8], radix: u32, digits: &[u8]) -> usize {
+                // 基数は高々64なので u8 にも収まる
+                let radix = radix as $t;
+                let mut n = self;
+                let mut cur = buf.len();
+
+                while {
+                    cur -= 1;
+                    buf[cur] = digits[(n % radix) as usize];
+                    n /= radix;
+
+                    n > 0
+                } {}
+
+                cur
+            }
+        }
+    )*};
+}
+
+impl_unsigned! {
+    u8, 3;
+    u16, 5;
+    u32, 10;
+    u64, 20;
+    usize, 20;
+}
+
+impl FormatInteger for u128 {
+    const MAX_LEN: usize = 39;
+
+    const BITS: u32 = 128;
+
+    type Buffer = [u8; 39];
+
+    fn new_buffer() -> Self::Buffer {
+        [0; 39]
+    }
+
+    type StrBuffer = [u8; 39];
+
+    fn new_str_buffer() -> Self::StrBuffer {
+        [0; 39]
+    }
+
+    fn is_nonnegative(self) -> bool {
+        true
+    }
+
+    fn write_abs(self, buf: &mut [u8]) -> usize {
+        write_digits_u128_into(self, buf, u64::write_abs)
+    }
+
+    fn write_abs_lut(self, buf: &mut [u8]) -> usize {
+        write_digits_u128_into(self, buf, u64::write_abs_lut)
+    }
+
+    fn abs_len(self) -> usize {
+        let mut len = 0;
+        let high = split_u128(self, |_| len += CHUNK_LEN);
+        len + decimal_len(high)
+    }
+
+    fn write_abs_exact(self, buf: &mut [MaybeUninit<u8>]) {
+        let mut end = buf.len();
+
+        // 下から19桁ずつ u64 で書く
+        let high = split_u128(self, |chunk| {
+            chunk.write_abs_exact(&mut buf[end - CHUNK_LEN..end]);
+            end -= CHUNK_LEN;
+        });
+
+        high.write_abs_exact(&mut buf[..end]);
+    }
+
+    fn write_bits(self, buf: &mut [u8], shift: u32, digits: &[u8; 16]) -> usize {
+        write_bits_body!(u128, self, buf, shift, digits)
+    }
+
+    fn write_abs_radix(self, buf: &mut [u8], radix: u32, digits: &[u8]) -> usize {
+        let mut n = self;
+        let mut cur = buf.len();
+
+        // 128ビット除算は遅いので、u64 に収まったら切り替える
+        while n > u128::from(u64::MAX) {
+            cur -= 1;
+            buf[cur] = digits[(n % u128::from(radix)) as usize];
+            n /= u128::from(radix);
+        }
+
+        (n as u64).write_abs_radix(&mut buf[..cur], radix, digits)
+    }
+}
+
+macro_rules! impl_signed {
+    ($($t:ty, $u:ty, $str_len:expr;)*) => {$(
+        impl FormatInteger for $t {
+            const MAX_LEN: usize = <$u as FormatInteger>::MAX_LEN;
+
+            const BITS: u32 = <$u as FormatInteger>::BITS;
+
+            type Buffer = <$u as FormatInteger>::Buffer;
+
+            fn new_buffer() -> Self::Buffer {
+                <$u>::new_buffer()
+            }
+
+            type StrBuffer = [u8; $str_len];
+
+            fn new_str_buffer() -> Self::StrBuffer {
+                [0; $str_len]
+            }
+
+            fn is_nonnegative(self) -> bool {
+                self >= 0
+            }
+
+            fn write_abs(self, buf: &mut [u8]) -> usize {
+                // MIN の絶対値は元の型に収まらないので符号なし型で扱う
+                self.unsigned_abs().write_abs(buf)
+            }
+
+            fn write_abs_lut(self, buf: &mut [u8]) -> usize {
+                self.unsigned_abs().write_abs_lut(buf)
+            }
+
+            fn abs_len(self) -> usize {
+                self.unsigned_abs().abs_len()
+            }
+
+            fn write_abs_exact(self, buf: &mut [MaybeUninit<u8>]) {
+                self.unsigned_abs().write_abs_exact(buf)
+            }
+
+            fn write_bits(self, buf: &mut [u8], shift: u32, digits: &[u8; 16]) -> usize {
+                // 負の値は2の補数表現のまま書く
+                (self as $u).write_bits(buf, shift, digits)
+            }
+
+            fn write_abs_radix(self, buf: &mut [u8], radix: u32, digits: &[u8]) -> usize {
+                self.unsigned_abs().write_abs_radix(buf, radix, digits)
+            }
+        }
+    )*};
+}
+
+// 符号の分だけ長いバッファが要る (i64 は絶対値が19桁なので u64 と同じで足りる)
+impl_signed! {
+    i8, u8, 4;
+    i16, u16, 6;
+    i32, u32, 11;
+    i64, u64, 20;
+    isize, usize, 20;
+    i128, u128, 40;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<T: FormatInteger>(vals: &[T]) {
+        for val in vals.iter().copied() {
+            let mut buf = T::new_buffer();
+            let buf = buf.as_mut();
+            assert_eq!(buf.len(), T::MAX_LEN);
+
+            let sign = if val.is_nonnegative() { "" } else { "-" };
+            assert!(T::new_str_buffer().as_ref().len() >= val.to_string().len());
+
+            let cur = val.write_abs(buf);
+            let actual = format!("{}{}", sign, std::str::from_utf8(&buf[cur..]).unwrap());
+            assert_eq!(actual, val.to_string());
+
+            let cur = val.write_abs_lut(buf);
+            let actual = format!("{}{}", sign, std::str::from_utf8(&buf[cur..]).unwrap());
+            assert_eq!(actual, val.to_string());
+
+            let len = val.abs_len();
+            assert_eq!(len, buf.len() - cur);
+            let mut exact = vec![MaybeUninit::uninit(); len];
+            val.write_abs_exact(&mut exact);
+            let exact: Vec<u8> = exact.iter().map(|b| unsafe { b.assume_init() }).collect();
+            assert_eq!(exact, &buf[cur..]);
+
+            let mut buf = [0u8; MAX_BITS_LEN];
+            let cur = val.write_bits(&mut buf, 1, b"0123456789abcdef");
+            let actual = std::str::from_utf8(&buf[cur..]).unwrap();
+            assert_eq!(actual, format!("{:b}", val));
+        }
+    }
+
+    #[test]
+    fn test_write_abs_bounds() {
+        check(&[0, 9, 10, 99, 100, u8::MAX]);
+        check(&[0, 9, 10, 99, 100, u16::MAX]);
+        check(&[0, 9, 10, 99, 100, u32::MAX]);
+        check(&[0, 9, 10, 99, 100, u64::MAX]);
+        check(&[0, 9, 10, 99, 100, usize::MAX]);
+        check(&[0, 9, 10, 99, 100, u128::MAX]);
+        check(&[
+            u128::from(u64::MAX),
+            u128::from(u64::MAX) + 1,
+            10u128.pow(19),
+            10u128.pow(38) - 1,
+            10u128.pow(38),
+        ]);
+        check(&[i8::MIN, -1, 0, i8::MAX]);
+        check(&[i16::MIN, -1, 0, i16::MAX]);
+        check(&[i32::MIN, -1, 0, i32::MAX]);
+        check(&[i64::MIN, -1, 0, i64::MAX]);
+        check(&[isize::MIN, -1, 0, isize::MAX]);
+        check(&[i128::MIN, -1, 0, i128::MAX]);
+    }
+}
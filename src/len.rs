@@ -0,0 +1,55 @@
+/// `10^i` (`i`は0から19まで)
+const POW10: [u64; 20] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    10_000_000_000,
+    100_000_000_000,
+    1_000_000_000_000,
+    10_000_000_000_000,
+    100_000_000_000_000,
+    1_000_000_000_000_000,
+    10_000_000_000_000_000,
+    100_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+    10_000_000_000_000_000_000,
+];
+
+/// `n`の10進表記の桁数を返す (`0`は1桁)
+///
+/// ビット長から桁数を見積もり、10の累乗の表と1回比べて補正する。ループや除算を使わない。
+pub fn decimal_len(n: u64) -> usize {
+    // n | 1 で 0 も 1 と同じ1桁として扱う
+    let n = n | 1;
+    let bits = 64 - n.leading_zeros();
+    // 1233 / 4096 は log10(2) の近似で、ビット長が64以下なら floor(log10(2^bits)) に一致する
+    let approx = ((bits * 1233) >> 12) as usize;
+    approx + 1 - usize::from(n < POW10[approx])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_len() {
+        assert_eq!(decimal_len(0), 1);
+        assert_eq!(decimal_len(u64::MAX), 20);
+        for (i, p) in POW10.iter().copied().enumerate() {
+            assert_eq!(decimal_len(p), i + 1);
+            assert_eq!(decimal_len(p - 1), i.max(1));
+        }
+        for shift in 0..64 {
+            let n = 1u64 << shift;
+            assert_eq!(decimal_len(n), n.to_string().len());
+            assert_eq!(decimal_len(n - 1), (n - 1).to_string().len());
+        }
+    }
+}
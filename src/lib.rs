@@ -0,0 +1,28 @@
+//! 整数型から文字列への素朴な変換アルゴリズムのライブラリ
+//!
+//! 比較のため、浮動小数点数の最短表記も扱う。
+
+mod exp;
+mod float;
+// 単体テストは常に実行し、公開APIとしては fuzzing フィーチャーの場合だけ提供する
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzz;
+mod integer;
+mod len;
+mod lut;
+mod parse;
+mod radix;
+mod ryu;
+mod simple;
+mod stats;
+mod wide;
+
+pub use float::{FloatDisplay, FloatKind, FormatFloat, FLOAT_MAX_LEN};
+pub use integer::FormatInteger;
+pub use len::decimal_len;
+pub use lut::LutDisplay;
+pub use parse::{parse, ParseError, ParseInteger};
+pub use radix::{max_radix_len, RadixDisplay, BASE36_DIGITS, BASE58_ALPHABET, BASE62_ALPHABET};
+pub use simple::{write_digits, SimpleDisplay, U64_MAX_LEN};
+pub use stats::{compare, reject_outliers, stats, OutlierFilter, Stats, Verdict};
+pub use wide::{write_digits_u128, U128_MAX_LEN};
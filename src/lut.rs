@@ -0,0 +1,52 @@
+use std::fmt::{self, Display};
+use std::str::from_utf8_unchecked;
+
+use crate::integer::FormatInteger;
+
+/// "00"から"99"までの2桁の数字列を並べた表
+pub(crate) const DEC_DIGITS_LUT: &[u8; 200] = b"\
+    0001020304050607080910111213141516171819\
+    2021222324252627282930313233343536373839\
+    4041424344454647484950515253545556575859\
+    6061626364656667686970717273747576777879\
+    8081828384858687888990919293949596979899";
+
+/// 2桁ずつ表引きするitoa実装のラッパー型
+///
+/// 除算1回あたり2桁を書き込むので、`SimpleDisplay`より除算の回数が半分になる。
+#[derive(Debug, Clone, Copy)]
+pub struct LutDisplay<T>(pub T);
+
+impl<T: FormatInteger> Display for LutDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = T::new_buffer();
+        let buf = buf.as_mut();
+        let cur = self.0.write_abs_lut(buf);
+
+        unsafe {
+            let buf_slice = from_utf8_unchecked(&buf[cur..]);
+            f.pad_integral(self.0.is_nonnegative(), "", buf_slice)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lut_display() {
+        let vals: [u64; 9] = [0, 1, 9, 10, 11, 99, 100, 101, 18446744073709551615];
+
+        for val in vals.iter().copied() {
+            let to_be = format!("{}", val);
+            let actual = format!("{}", LutDisplay(val));
+            assert_eq!(actual, to_be);
+        }
+
+        assert_eq!(format!("{}", LutDisplay(i8::MIN)), "-128");
+        assert_eq!(format!("{}", LutDisplay(u128::MAX)), u128::MAX.to_string());
+        assert_eq!(format!("{}", LutDisplay(i128::MIN)), i128::MIN.to_string());
+        assert_eq!(format!("{:>6}", LutDisplay(-42i32)), "   -42");
+    }
+}
@@ -1,18 +1,37 @@
-use std::fmt::{self, Display};
-use std::io::Write;
-use std::str::from_utf8_unchecked;
-use std::time::Instant;
+mod bench;
+mod chart;
+mod compare;
+mod dataset;
+mod distribution;
+mod output;
+mod registry;
+mod report;
+mod table;
+mod verify;
 
-use anyhow::Result;
-use clap::{App, Arg};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::{App, Arg, SubCommand};
 use rand::{Rng, SeedableRng};
 use rand_xorshift::XorShiftRng;
 
-const BENCH_SIZE: usize = 1_000_000;
-const BENCH_ITER: usize = 100;
+#[cfg(feature = "fuzzing")]
+use itoa_example::fuzz;
+
+use bench::{run_bench, Config};
+use chart::render_svg;
+use compare::{compare_tables, print_changes, CompareConfig};
+use dataset::InputFile;
+use output::{DigitsResult, Output};
+use report::{render_html, RunInfo};
+use table::ResultTable;
+use verify::{u64_boundaries, verify};
 
 fn main() -> Result<()> {
-    let matches = App::new("itoa-example")
+    let app = App::new("itoa-example")
         .arg(
             Arg::with_name("seed")
                 .short("s")
@@ -20,146 +39,419 @@ fn main() -> Result<()> {
                 .takes_value(true)
                 .help("RNG seed"),
         )
-        .get_matches();
+        .arg(
+            Arg::with_name("type")
+                .short("t")
+                .long("type")
+                .takes_value(true)
+                .possible_values(&[
+                    "u8", "u16", "u32", "u64", "usize", "u128", "i8", "i16", "i32", "i64", "isize",
+                    "i128", "f32", "f64",
+                ])
+                .default_value("u64")
+                .help("Integer or floating-point type to benchmark"),
+        )
+        .arg(
+            Arg::with_name("size")
+                .short("n")
+                .long("size")
+                .takes_value(true)
+                .default_value("1000000")
+                .help("Number of values per iteration"),
+        )
+        .arg(
+            Arg::with_name("iter")
+                .short("i")
+                .long("iter")
+                .takes_value(true)
+                .default_value("100")
+                .help("Number of iterations per digit count"),
+        )
+        .arg(
+            Arg::with_name("min-digits")
+                .long("min-digits")
+                .takes_value(true)
+                .default_value("1")
+                .help("Minimum digit count"),
+        )
+        .arg(
+            Arg::with_name("max-digits")
+                .long("max-digits")
+                .takes_value(true)
+                .help("Maximum digit count [default: maximum of the type]"),
+        )
+        .arg(
+            Arg::with_name("time-budget")
+                .long("time-budget")
+                .takes_value(true)
+                .help("Time limit in seconds per digit count"),
+        )
+        .arg(
+            Arg::with_name("warmup")
+                .long("warmup")
+                .takes_value(true)
+                .default_value("1")
+                .help("Number of discarded warm-up iterations per digit count"),
+        )
+        .arg(
+            Arg::with_name("outliers")
+                .long("outliers")
+                .takes_value(true)
+                .possible_values(&["none", "iqr", "mad"])
+                .default_value("none")
+                .help("Outlier rejection method"),
+        )
+        .arg(
+            Arg::with_name("distribution")
+                .short("d")
+                .long("distribution")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .possible_values(&[
+                    "digits",
+                    "uniform",
+                    "log-uniform",
+                    "benford",
+                    "zipf",
+                    "counter",
+                ])
+                .default_value("digits")
+                .help("Input value distribution, one result row each (repeatable)"),
+        )
+        .arg(
+            Arg::with_name("input")
+                .long("input")
+                .takes_value(true)
+                .value_name("FILE")
+                .help(
+                    "Benchmarks values read from FILE instead of random values \
+                     (--min-digits and --max-digits are ignored)",
+                ),
+        )
+        .arg(
+            Arg::with_name("input-format")
+                .long("input-format")
+                .takes_value(true)
+                .possible_values(&["text", "binary"])
+                .default_value("text")
+                .help(
+                    "Format of --input: one value per line, or little-endian u64 \
+                     (two's complement i64 for signed types)",
+                ),
+        )
+        .arg(
+            Arg::with_name("trait")
+                .long("trait")
+                .takes_value(true)
+                .possible_values(&[
+                    "display",
+                    "lower-hex",
+                    "upper-hex",
+                    "octal",
+                    "binary",
+                    "lower-exp",
+                    "upper-exp",
+                ])
+                .default_value("display")
+                .help("Formatting trait to benchmark"),
+        )
+        .arg(
+            Arg::with_name("parse")
+                .long("parse")
+                .help("Benchmarks parsing text into integers instead of formatting"),
+        )
+        .arg(
+            Arg::with_name("len")
+                .long("len")
+                .conflicts_with("parse")
+                .help("Benchmarks counting decimal digits of u64 instead of formatting"),
+        )
+        .arg(
+            Arg::with_name("exp-step")
+                .long("exp-step")
+                .takes_value(true)
+                .default_value("10")
+                .help("Step of the decimal exponent between rows for f32 and f64"),
+        )
+        .arg(
+            Arg::with_name("output-format")
+                .short("f")
+                .long("output-format")
+                .takes_value(true)
+                .possible_values(&["tsv", "csv", "json", "markdown"])
+                .default_value("tsv")
+                .help("Output format of the result table"),
+        )
+        .arg(
+            Arg::with_name("chart")
+                .long("chart")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Also writes an SVG chart of the results to FILE"),
+        )
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("Compares results against a saved baseline and fails on regression")
+                .arg(
+                    Arg::with_name("baseline")
+                        .required(true)
+                        .help("Baseline results file (TSV or CSV)"),
+                )
+                .arg(
+                    Arg::with_name("current")
+                        .help("Results file to compare [default: rerun the benchmark]"),
+                )
+                .arg(
+                    Arg::with_name("threshold")
+                        .long("threshold")
+                        .takes_value(true)
+                        .default_value("5")
+                        .help("Slowdown in percent regarded as a regression"),
+                )
+                .arg(
+                    Arg::with_name("impl")
+                        .long("impl")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Implementation to compare (repeatable) [default: all]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("chart")
+                .about("Renders an SVG chart from a saved results file")
+                .arg(
+                    Arg::with_name("input")
+                        .required(true)
+                        .help("Results file (TSV or CSV)"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .required(true)
+                        .help("SVG file to write"),
+                )
+                .arg(
+                    Arg::with_name("title")
+                        .long("title")
+                        .takes_value(true)
+                        .help("Chart title [default: input file name]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("report")
+                .about("Runs the benchmark and writes a self-contained HTML report")
+                .arg(
+                    Arg::with_name("output")
+                        .required(true)
+                        .help("HTML file to write"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Checks every u32 and u64 digit boundaries against std")
+                .arg(
+                    Arg::with_name("radius")
+                        .long("radius")
+                        .takes_value(true)
+                        .default_value("1000")
+                        .help("Checks u64 values within this distance of each power of ten"),
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .takes_value(true)
+                        .help("Number of threads [default: number of CPUs]"),
+                ),
+        );
+    // fuzz サブコマンドは fuzzing フィーチャーを有効にした場合だけ使える
+    #[cfg(feature = "fuzzing")]
+    let app = app.subcommand(
+        SubCommand::with_name("fuzz")
+            .about("Compares random values and format flags against std")
+            .arg(
+                Arg::with_name("runs")
+                    .long("runs")
+                    .takes_value(true)
+                    .default_value("1000000")
+                    .help("Number of random inputs"),
+            ),
+    );
+    let matches = app.get_matches();
 
-    let mut rng = if let Some(seed_str) = matches.value_of("seed") {
-        let seed: u64 = seed_str.parse()?;
-        XorShiftRng::seed_from_u64(seed)
-    } else {
-        XorShiftRng::from_entropy()
+    // 再現できるよう、指定がない場合もシードを決めて表示する
+    let seed: u64 = match matches.value_of("seed") {
+        Some(seed_str) => seed_str.parse()?,
+        None => XorShiftRng::from_entropy().gen(),
     };
+    eprintln!("Seed: {}", seed);
+    let mut rng = XorShiftRng::seed_from_u64(seed);
 
-    println!("Digits\tSimpleAvg\tSimpleMin\tSimpleMax\tStdAvg\tStdMin\tStdMax");
-    for digits in 1..20 {
-        bench_for_digits(&mut rng, digits);
+    let config = Config {
+        size: matches.value_of("size").unwrap().parse()?,
+        iter: matches.value_of("iter").unwrap().parse()?,
+        min_digits: matches.value_of("min-digits").unwrap().parse()?,
+        max_digits: match matches.value_of("max-digits") {
+            Some(s) => s.parse()?,
+            None => u32::MAX,
+        },
+        time_budget: match matches.value_of("time-budget") {
+            Some(s) => Some(Duration::from_secs_f64(s.parse()?)),
+            None => None,
+        },
+        warmup: matches.value_of("warmup").unwrap().parse()?,
+        outliers: matches.value_of("outliers").unwrap().parse()?,
+        distributions: matches
+            .values_of("distribution")
+            .unwrap()
+            .map(str::parse)
+            .collect::<Result<_>>()?,
+        input: match matches.value_of("input") {
+            Some(path) => Some(InputFile {
+                path: path.into(),
+                format: matches.value_of("input-format").unwrap().parse()?,
+            }),
+            None => None,
+        },
+        format_trait: matches.value_of("trait").unwrap().parse()?,
+        parse: matches.is_present("parse"),
+        len: matches.is_present("len"),
+        exp_step: matches.value_of("exp-step").unwrap().parse()?,
+    };
+    if config.size == 0 || config.iter == 0 {
+        bail!("--size and --iter must be positive");
+    }
+    if config.min_digits == 0 || config.min_digits > config.max_digits {
+        bail!("invalid digit range");
+    }
+    // 指数の範囲は f64 でも 700 に満たないので、それより大きな幅は意味がない
+    if config.exp_step == 0 || config.exp_step > 1000 {
+        bail!("--exp-step must be between 1 and 1000");
     }
 
-    Ok(())
-}
-
-fn bench_for_digits(rng: &mut impl Rng, digits: u32) {
-    let value_min = 10u64.pow(digits - 1);
-
-    eprintln!(
-        "For {} digits ({} ~ {}):",
-        digits,
-        value_min,
-        value_min * 10 - 1
-    );
+    let int_type = matches.value_of("type").unwrap();
 
-    let mut simple_times = Vec::<f64>::new();
-    let mut std_times = Vec::<f64>::new();
+    #[cfg(feature = "fuzzing")]
+    if let Some(sub) = matches.subcommand_matches("fuzz") {
+        return run_fuzz(&mut rng, sub.value_of("runs").unwrap().parse()?);
+    }
 
-    for _ in 0..BENCH_ITER {
-        let mut values = Vec::with_capacity(BENCH_SIZE);
-        for _ in 0..BENCH_SIZE {
-            values.push(rng.gen_range(value_min, value_min * 10));
-        }
+    if let Some(sub) = matches.subcommand_matches("verify") {
+        let threads = match sub.value_of("threads") {
+            Some(s) => s.parse()?,
+            None => thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        return run_verify(sub.value_of("radius").unwrap().parse()?, threads);
+    }
 
-        let mut w_simple = Vec::<u8>::with_capacity(21 * BENCH_SIZE);
-        let start = Instant::now();
-        for v in values.iter().copied() {
-            write!(w_simple, "{},", SimpleDisplay(v)).unwrap();
-        }
-        simple_times.push(start.elapsed().as_secs_f64());
+    if let Some(sub) = matches.subcommand_matches("compare") {
+        let compare_config = CompareConfig {
+            threshold: sub.value_of("threshold").unwrap().parse()?,
+            impls: sub
+                .values_of("impl")
+                .map(|vs| vs.map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+        let baseline = ResultTable::load(sub.value_of("baseline").unwrap())?;
+        let current = match sub.value_of("current") {
+            Some(path) => ResultTable::load(path)?,
+            None => {
+                let results = run_bench(&mut rng, int_type, &config, None)?;
+                check_verified(&results)?;
+                ResultTable::from_results(&results)
+            }
+        };
+        let changes = compare_tables(&baseline, &current, &compare_config);
+        return print_changes(&changes, &compare_config);
+    }
 
-        let mut w_std = Vec::<u8>::with_capacity(21 * BENCH_SIZE);
-        let start = Instant::now();
-        for v in values.iter().copied() {
-            write!(w_std, "{},", v).unwrap();
-        }
-        std_times.push(start.elapsed().as_secs_f64());
+    if let Some(sub) = matches.subcommand_matches("chart") {
+        let input = sub.value_of("input").unwrap();
+        let table = ResultTable::load(input)?;
+        let title = sub.value_of("title").unwrap_or(input);
+        return write_file(sub.value_of("output").unwrap(), &render_svg(&table, title));
+    }
 
-        assert_eq!(w_simple, w_std);
+    if let Some(sub) = matches.subcommand_matches("report") {
+        let results = run_bench(&mut rng, int_type, &config, None)?;
+        let info = RunInfo {
+            int_type: int_type.to_string(),
+            seed,
+            config,
+        };
+        write_file(
+            sub.value_of("output").unwrap(),
+            &render_html(&info, &results),
+        )?;
+        return check_verified(&results);
     }
 
-    let simple_stats = stats(&simple_times);
-    let std_stats = stats(&std_times);
+    let mut output = Output::new(matches.value_of("output-format").unwrap().parse()?);
+    let results = run_bench(&mut rng, int_type, &config, Some(&mut output))?;
 
-    eprintln!(
-        "    Simple: avg = {:.3}s, min = {:.3}s, max = {:.3}s",
-        simple_stats.avg, simple_stats.min, simple_stats.max
-    );
-    eprintln!(
-        "    Std:    avg = {:.3}s, min = {:.3}s, max = {:.3}s",
-        std_stats.avg, std_stats.min, std_stats.max
-    );
+    if let Some(path) = matches.value_of("chart") {
+        let table = ResultTable::from_results(&results);
+        let title = format!("itoa-example ({})", int_type);
+        write_file(path, &render_svg(&table, &title))?;
+    }
 
-    println!(
-        "{}\t{:.6}\t{:.6}\t{:.6}\t{:.6}\t{:.6}\t{:.6}",
-        digits,
-        simple_stats.avg,
-        simple_stats.min,
-        simple_stats.max,
-        std_stats.avg,
-        std_stats.min,
-        std_stats.max
-    );
+    check_verified(&results)
 }
 
-/// 素朴なitoa実装のラッパー型
-#[derive(Debug, Clone, Copy)]
-struct SimpleDisplay(u64);
-
-impl Display for SimpleDisplay {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut n = self.0;
-
-        let mut buf = *b"18446744073709551615";
-        let mut cur = buf.len();
-
-        while {
-            // do-while と等価なイディオム
-            cur -= 1;
-            let m = n % 10;
-            n = n / 10;
-            buf[cur] = (m as u8) + b'0';
-
-            n > 0
-        } {}
-
-        unsafe {
-            let buf_slice = from_utf8_unchecked(&buf[cur..]);
-            f.pad_integral(true, "", buf_slice)
+/// 乱数で作った`runs`個の入力を`fuzz::check_bytes`で検査する
+#[cfg(feature = "fuzzing")]
+fn run_fuzz(rng: &mut XorShiftRng, runs: u64) -> Result<()> {
+    for _ in 0..runs {
+        let len = rng.gen_range(4, 21);
+        let data: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        if let Err(e) = fuzz::check_bytes(&data) {
+            let hex: Vec<String> = data.iter().map(|b| format!("{:02x}", b)).collect();
+            bail!("{}\n    input: {}", e, hex.join(" "));
         }
     }
+    eprintln!("{} inputs ok", runs);
+    Ok(())
 }
 
-fn stats(vs: &[f64]) -> Stats {
-    Stats {
-        min: vs
-            .iter()
-            .copied()
-            .min_by(|x, y| x.partial_cmp(y).unwrap())
-            .unwrap(),
-        max: vs
-            .iter()
-            .copied()
-            .max_by(|x, y| x.partial_cmp(y).unwrap())
-            .unwrap(),
-        avg: vs.iter().copied().sum::<f64>() / (BENCH_ITER as f64),
+/// 全ての`u32`と`u64`の桁の境界付近の値について、各実装の出力を基準の実装と比べる
+fn run_verify(radius: u64, threads: usize) -> Result<()> {
+    eprintln!("Verifying all u32 values with {} threads", threads);
+    let start = Instant::now();
+    if let Some(m) = verify(1 << 32, |i| i as u32, threads) {
+        bail!("u32: {}", m);
     }
-}
+    eprintln!("    ok ({:.1}s)", start.elapsed().as_secs_f64());
 
-#[derive(Debug, Clone, Copy)]
-struct Stats {
-    avg: f64,
-    min: f64,
-    max: f64,
+    let values = u64_boundaries(radius);
+    eprintln!(
+        "Verifying {} u64 values around powers of ten (radius {})",
+        values.len(),
+        radius
+    );
+    if let Some(m) = verify(values.len() as u64, |i| values[i as usize], threads) {
+        bail!("u64: {}", m);
+    }
+    eprintln!("    ok");
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_simple_display() {
-        let vals = [0, 1, 9, 10, 11, 18446744073709551615];
-
-        for val in vals.iter().copied() {
-            let to_be = format!("{}", val);
-            let actual = format!("{}", SimpleDisplay(val));
-            assert_eq!(actual, to_be);
-        }
+/// 全ての実装の出力が基準の実装と一致したことを確認する
+fn check_verified(results: &[DigitsResult]) -> Result<()> {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.verified())
+        .map(|r| r.label.as_str())
+        .collect();
+    if !failed.is_empty() {
+        bail!(
+            "output differs from the reference implementation for digits {}",
+            failed.join(", ")
+        );
     }
+    Ok(())
+}
+
+/// `contents`をファイル`path`に書き出す
+fn write_file(path: &str, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("failed to write {}", path))
 }
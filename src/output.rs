@@ -0,0 +1,328 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Error};
+
+use itoa_example::{Stats, Verdict};
+
+/// 実装ごとに出力する統計値の列名
+pub const STATS_COLUMNS: [&str; 9] = [
+    "Avg", "Min", "Max", "Median", "StdDev", "P5", "P95", "CiLow", "CiHigh",
+];
+
+/// 実装ごとに出力するスループットの列名
+pub const THROUGHPUT_COLUMNS: [&str; 3] = ["NsPerValue", "ValuesPerSec", "MBPerSec"];
+
+/// ある桁数での計測結果
+#[derive(Debug, Clone)]
+pub struct DigitsResult {
+    /// `Digits`列の値 (負の値の場合は`-3`のように表す)
+    pub label: String,
+    /// 値の範囲の下限
+    pub value_min: String,
+    /// 値の範囲の上限
+    pub value_max: String,
+    /// 実装ごとの結果 (登録順)
+    pub impls: Vec<ImplResult>,
+}
+
+/// ある実装の計測結果
+#[derive(Debug, Clone)]
+pub struct ImplResult {
+    pub name: &'static str,
+    pub is_reference: bool,
+    /// 外れ値の除去前の全ての計測値(秒)
+    pub samples: Vec<f64>,
+    /// 外れ値の除去後の統計値
+    pub stats: Stats,
+    /// 基準の実装と比べた判定 (基準の実装自身は`None`)
+    pub verdict: Option<Verdict>,
+    /// 出力を基準の実装と照合した値の数 (捨て計測の分も含む)
+    pub checked_values: usize,
+    /// 出力が基準の実装と一致しなかった計測の回数
+    pub mismatched_iters: usize,
+    /// 1回の計測で書き出した値の数
+    pub values_per_iter: usize,
+    /// 1回の計測で書き出したバイト数の平均 (区切り文字を含む)
+    pub bytes_per_iter: f64,
+}
+
+impl ImplResult {
+    /// 値1個あたりの平均時間(ナノ秒)
+    pub fn ns_per_value(&self) -> f64 {
+        self.stats.avg / self.values_per_iter as f64 * 1e9
+    }
+
+    /// 1秒あたりに書き出せる値の数
+    pub fn values_per_sec(&self) -> f64 {
+        self.values_per_iter as f64 / self.stats.avg
+    }
+
+    /// 出力の書き出し速度(MB/s、1MB = 10^6バイト)
+    pub fn mb_per_sec(&self) -> f64 {
+        self.bytes_per_iter / self.stats.avg / 1e6
+    }
+}
+
+impl DigitsResult {
+    /// 全ての実装の出力が基準の実装と一致したかどうか
+    pub fn verified(&self) -> bool {
+        self.impls.iter().all(|r| r.mismatched_iters == 0)
+    }
+}
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Tsv,
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tsv" => Ok(OutputFormat::Tsv),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            "markdown" => Ok(OutputFormat::Markdown),
+            _ => bail!("unknown output format: {}", s),
+        }
+    }
+}
+
+/// 計測結果を標準出力に書き出す
+///
+/// 桁数ごとの結果が出るたびに1行(JSONでは1オブジェクト)ずつ書き出す。
+#[derive(Debug)]
+pub struct Output {
+    format: OutputFormat,
+    rows: usize,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Output {
+        Output { format, rows: 0 }
+    }
+
+    /// 表の見出しを書き出す
+    ///
+    /// `impls`は実装の名前と、基準の実装かどうかの組。
+    pub fn begin(&mut self, impls: &[(&str, bool)]) {
+        let columns = columns(impls);
+
+        match self.format {
+            OutputFormat::Tsv => println!("{}", columns.join("\t")),
+            OutputFormat::Csv => println!("{}", columns.join(",")),
+            OutputFormat::Json => println!("["),
+            OutputFormat::Markdown => {
+                println!("| {} |", columns.join(" | "));
+                let seps: Vec<&str> = columns.iter().map(|_| "---:").collect();
+                println!("|{}|", seps.join("|"));
+            }
+        }
+    }
+
+    /// 1つの桁数の結果を書き出す
+    pub fn row(&mut self, result: &DigitsResult) {
+        match self.format {
+            OutputFormat::Tsv => println!("{}", cells(result).join("\t")),
+            OutputFormat::Csv => println!("{}", cells(result).join(",")),
+            OutputFormat::Json => {
+                let sep = if self.rows == 0 { "" } else { "," };
+                println!("{}{}", sep, to_json(result));
+            }
+            OutputFormat::Markdown => println!("| {} |", cells(result).join(" | ")),
+        }
+        self.rows += 1;
+    }
+
+    /// 出力を閉じる
+    pub fn end(&mut self) {
+        if self.format == OutputFormat::Json {
+            println!("]");
+        }
+    }
+}
+
+/// 表形式の見出し
+///
+/// `impls`は実装の名前と、基準の実装かどうかの組。
+pub fn columns(impls: &[(&str, bool)]) -> Vec<String> {
+    let mut columns = vec!["Digits".to_string()];
+    for (name, is_reference) in impls.iter() {
+        for col in STATS_COLUMNS.iter().chain(THROUGHPUT_COLUMNS.iter()) {
+            columns.push(format!("{}{}", name, col));
+        }
+        if !is_reference {
+            columns.push(format!("{}Verdict", name));
+        }
+    }
+    columns
+}
+
+/// 表形式の1行分のセル
+pub fn cells(result: &DigitsResult) -> Vec<String> {
+    let mut cells = vec![result.label.clone()];
+    for r in result.impls.iter() {
+        let s = &r.stats;
+        for v in [
+            s.avg, s.min, s.max, s.median, s.std_dev, s.p5, s.p95, s.ci_low, s.ci_high,
+        ]
+        .iter()
+        {
+            cells.push(format!("{:.6}", v));
+        }
+        cells.push(format!("{:.3}", r.ns_per_value()));
+        cells.push(format!("{:.0}", r.values_per_sec()));
+        cells.push(format!("{:.3}", r.mb_per_sec()));
+        if let Some(verdict) = r.verdict {
+            cells.push(verdict.to_string());
+        }
+    }
+    cells
+}
+
+/// 1つの桁数の結果をJSONのオブジェクトにする
+fn to_json(result: &DigitsResult) -> String {
+    let mut out = String::new();
+    write!(
+        out,
+        "{{\"digits\":{},\"min\":{},\"max\":{},\"impls\":[",
+        json_string(&result.label),
+        json_string(&result.value_min),
+        json_string(&result.value_max)
+    )
+    .unwrap();
+
+    for (i, r) in result.impls.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let s = &r.stats;
+        write!(
+            out,
+            "{{\"name\":{},\"reference\":{},\"avg\":{},\"min\":{},\"max\":{},\"median\":{},\
+             \"std_dev\":{},\"p5\":{},\"p95\":{},\"ci_low\":{},\"ci_high\":{},\"kept\":{},\
+             \"checked_values\":{},\"mismatched_iters\":{},\"ns_per_value\":{},\
+             \"values_per_sec\":{},\"mb_per_sec\":{},",
+            json_string(r.name),
+            r.is_reference,
+            json_number(s.avg),
+            json_number(s.min),
+            json_number(s.max),
+            json_number(s.median),
+            json_number(s.std_dev),
+            json_number(s.p5),
+            json_number(s.p95),
+            json_number(s.ci_low),
+            json_number(s.ci_high),
+            s.n,
+            r.checked_values,
+            r.mismatched_iters,
+            json_number(r.ns_per_value()),
+            json_number(r.values_per_sec()),
+            json_number(r.mb_per_sec())
+        )
+        .unwrap();
+        match r.verdict {
+            Some(v) => write!(out, "\"verdict\":{},", json_string(&v.to_string())).unwrap(),
+            None => out.push_str("\"verdict\":null,"),
+        }
+        let samples: Vec<String> = r.samples.iter().map(|&v| json_number(v)).collect();
+        write!(out, "\"samples\":[{}]}}", samples.join(",")).unwrap();
+    }
+
+    out.push_str("]}");
+    out
+}
+
+/// JSONの数値にする
+///
+/// JSONには無限大とNaNがないので、有限でない値は`null`にする。
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// JSONの文字列リテラルにする
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itoa_example::stats;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("Simple"), "\"Simple\"");
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\u000a\"");
+    }
+
+    #[test]
+    fn test_json_number() {
+        assert_eq!(json_number(1.5), "1.5");
+        assert_eq!(json_number(f64::INFINITY), "null");
+        assert_eq!(json_number(f64::NAN), "null");
+    }
+
+    #[test]
+    fn test_cells() {
+        let samples = vec![1.0, 2.0];
+        let imp = |name, is_reference| ImplResult {
+            name,
+            is_reference,
+            samples: samples.clone(),
+            stats: stats(&samples),
+            verdict: if is_reference {
+                None
+            } else {
+                Some(Verdict::Same)
+            },
+            checked_values: 2,
+            mismatched_iters: 0,
+            values_per_iter: 1000,
+            bytes_per_iter: 4000.0,
+        };
+        let result = DigitsResult {
+            label: "3".to_string(),
+            value_min: "100".to_string(),
+            value_max: "999".to_string(),
+            impls: vec![imp("Simple", false), imp("Std", true)],
+        };
+
+        let cells = cells(&result);
+        assert_eq!(
+            cells.len(),
+            columns(&[("Simple", false), ("Std", true)]).len()
+        );
+        let group = STATS_COLUMNS.len() + THROUGHPUT_COLUMNS.len();
+        assert_eq!(cells.len(), 1 + group * 2 + 1);
+        assert_eq!(cells[0], "3");
+        assert_eq!(cells[1], "1.500000");
+        // 1.5秒で1000個、4000バイト
+        assert_eq!(cells[STATS_COLUMNS.len() + 1], "1500000.000");
+        assert_eq!(cells[STATS_COLUMNS.len() + 2], "667");
+        assert_eq!(cells[STATS_COLUMNS.len() + 3], "0.003");
+        assert_eq!(cells[group + 1], "same");
+    }
+}
@@ -0,0 +1,247 @@
+use std::error::Error;
+use std::fmt;
+
+/// 整数の解析に失敗した理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// 入力が空
+    Empty,
+    /// 符号しかない
+    SignOnly,
+    /// 指定した位置(バイト単位)に数字でない文字がある
+    InvalidDigit(usize),
+    /// 型の最大値を超えた
+    PosOverflow,
+    /// 型の最小値を下回った
+    NegOverflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => f.write_str("cannot parse integer from empty string"),
+            ParseError::SignOnly => f.write_str("no digits after sign"),
+            ParseError::InvalidDigit(i) => write!(f, "invalid digit at byte {}", i),
+            ParseError::PosOverflow => f.write_str("number too large to fit in target type"),
+            ParseError::NegOverflow => f.write_str("number too small to fit in target type"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// 素朴なatoi実装で扱える整数型
+pub trait ParseInteger: Sized {
+    /// 10進表記を解析する
+    ///
+    /// 先頭に`+`を、符号付き整数型では`-`も付けられる。空白などは受け付けない。
+    fn parse_bytes(s: &[u8]) -> Result<Self, ParseError>;
+}
+
+/// 10進表記`s`を`T`として解析する
+pub fn parse<T: ParseInteger>(s: &[u8]) -> Result<T, ParseError> {
+    T::parse_bytes(s)
+}
+
+/// 符号を取り除き、(負かどうか, 数字列, 数字列の開始位置)を返す
+fn split_sign(s: &[u8], signed: bool) -> Result<(bool, &[u8], usize), ParseError> {
+    let (negative, digits) = match s {
+        [] => return Err(ParseError::Empty),
+        [b'+', rest @ ..] => (false, rest),
+        [b'-', rest @ ..] if signed => (true, rest),
+        _ => return Ok((false, s, 0)),
+    };
+    if digits.is_empty() {
+        return Err(ParseError::SignOnly);
+    }
+    Ok((negative, digits, 1))
+}
+
+/// 数字1文字の値を返す
+fn digit(c: u8, index: usize) -> Result<u8, ParseError> {
+    let d = c.wrapping_sub(b'0');
+    if d > 9 {
+        return Err(ParseError::InvalidDigit(index));
+    }
+    Ok(d)
+}
+
+/// `n`の10進表記の桁数
+const fn decimal_digits(mut n: u128) -> usize {
+    let mut len = 1;
+    while n >= 10 {
+        n /= 10;
+        len += 1;
+    }
+    len
+}
+
+macro_rules! impl_parse {
+    ($($t:ty, $signed:expr;)*) => {$(
+        impl ParseInteger for $t {
+            fn parse_bytes(s: &[u8]) -> Result<Self, ParseError> {
+                let (negative, digits, offset) = split_sign(s, $signed)?;
+
+                // 桁数が最大値より少なければ溢れないので検査を省く
+                const SAFE_LEN: usize = decimal_digits(<$t>::MAX as u128) - 1;
+                if digits.len() <= SAFE_LEN {
+                    let mut n: $t = 0;
+                    for (i, &c) in digits.iter().enumerate() {
+                        let d = digit(c, offset + i)? as $t;
+                        n = if negative { n * 10 - d } else { n * 10 + d };
+                    }
+                    return Ok(n);
+                }
+
+                // 負の値は MIN まで表せるよう、負の方向に積み上げる
+                let mut n: $t = 0;
+                for (i, &c) in digits.iter().enumerate() {
+                    let d = digit(c, offset + i)? as $t;
+                    n = if negative {
+                        n.checked_mul(10)
+                            .and_then(|n| n.checked_sub(d))
+                            .ok_or(ParseError::NegOverflow)?
+                    } else {
+                        n.checked_mul(10)
+                            .and_then(|n| n.checked_add(d))
+                            .ok_or(ParseError::PosOverflow)?
+                    };
+                }
+                Ok(n)
+            }
+        }
+    )*};
+}
+
+impl_parse! {
+    u8, false;
+    u16, false;
+    u32, false;
+    u64, false;
+    usize, false;
+    u128, false;
+    i8, true;
+    i16, true;
+    i32, true;
+    i64, true;
+    isize, true;
+    i128, true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleDisplay;
+
+    use std::num::IntErrorKind;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+
+    /// 標準ライブラリのエラーの種類に対応付ける
+    fn kind(e: ParseError) -> IntErrorKind {
+        match e {
+            ParseError::Empty => IntErrorKind::Empty,
+            ParseError::SignOnly | ParseError::InvalidDigit(_) => IntErrorKind::InvalidDigit,
+            ParseError::PosOverflow => IntErrorKind::PosOverflow,
+            ParseError::NegOverflow => IntErrorKind::NegOverflow,
+        }
+    }
+
+    fn check<T>(s: &[u8])
+    where
+        T: ParseInteger + std::str::FromStr<Err = std::num::ParseIntError> + PartialEq + fmt::Debug,
+    {
+        let expected = std::str::from_utf8(s)
+            .unwrap()
+            .parse::<T>()
+            .map_err(|e| *e.kind());
+        assert_eq!(parse::<T>(s).map_err(kind), expected, "{:?}", s);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse::<u64>(b"18446744073709551615"), Ok(u64::MAX));
+        assert_eq!(
+            parse::<u64>(b"18446744073709551616"),
+            Err(ParseError::PosOverflow)
+        );
+        assert_eq!(parse::<i64>(b"-9223372036854775808"), Ok(i64::MIN));
+        assert_eq!(
+            parse::<i64>(b"-9223372036854775809"),
+            Err(ParseError::NegOverflow)
+        );
+        assert_eq!(parse::<i64>(b"+42"), Ok(42));
+        assert_eq!(parse::<u8>(b"-0"), Err(ParseError::InvalidDigit(0)));
+        assert_eq!(parse::<i32>(b"12a"), Err(ParseError::InvalidDigit(2)));
+        assert_eq!(parse::<i32>(b"-"), Err(ParseError::SignOnly));
+        assert_eq!(parse::<i32>(b""), Err(ParseError::Empty));
+        assert_eq!(
+            parse::<u128>(b"00000000000000000000000000000000000000000001"),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn test_parse_like_std() {
+        let cases: [&[u8]; 12] = [
+            b"",
+            b"+",
+            b"-",
+            b"0",
+            b"-0",
+            b"+-1",
+            b"255",
+            b"256",
+            b"-128",
+            b"-129",
+            b"1 ",
+            b"99999999999999999999999999999999999999999x",
+        ];
+        for s in cases.iter() {
+            check::<u8>(s);
+            check::<i8>(s);
+            check::<u64>(s);
+            check::<i64>(s);
+            check::<i128>(s);
+        }
+
+        let mut rng = XorShiftRng::seed_from_u64(1);
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0, 42);
+            let s: Vec<u8> = (0..len)
+                .map(|i| match rng.gen_range(0, 20) {
+                    0 if i == 0 => b'-',
+                    1 if i == 0 => b'+',
+                    2 => b'x',
+                    _ => b'0' + rng.gen_range(0, 10),
+                })
+                .collect();
+            check::<u16>(&s);
+            check::<i32>(&s);
+            check::<u64>(&s);
+            check::<i64>(&s);
+            check::<u128>(&s);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = XorShiftRng::seed_from_u64(2);
+        for _ in 0..10_000 {
+            let v: i64 = rng.gen::<i64>() >> rng.gen_range(0, 64);
+            let mut out = Vec::new();
+            SimpleDisplay(v).append_to(&mut out);
+            assert_eq!(parse::<i64>(&out), Ok(v));
+
+            let v = v as u64;
+            out.clear();
+            SimpleDisplay(v).append_to(&mut out);
+            assert_eq!(parse::<u64>(&out), Ok(v));
+        }
+        assert_eq!(
+            parse::<i128>(i128::MIN.to_string().as_bytes()),
+            Ok(i128::MIN)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::{self, Display};
+use std::str::from_utf8_unchecked;
+
+use crate::integer::{FormatInteger, MAX_BITS_LEN};
+
+/// 2〜36進数で使う数字 (`0-9a-z`)
+pub const BASE36_DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// 62進数のアルファベット (`0-9A-Za-z`)
+pub const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// 58進数のアルファベット (Bitcoinと同じく`0OIl`を除いたもの)
+pub const BASE58_ALPHABET: &[u8; 58] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// `bits`ビットの符号なし整数の`radix`進表記の最大桁数
+pub const fn max_radix_len(bits: u32, radix: u32) -> usize {
+    let mut n = u128::MAX >> (128 - bits);
+    let mut len = 0;
+    while n > 0 {
+        n /= radix as u128;
+        len += 1;
+    }
+    len
+}
+
+/// 任意の基数で書式化するラッパー型
+///
+/// 負の値は標準ライブラリの`from_str_radix`と同じく、`-`と絶対値で表す。
+#[derive(Debug, Clone, Copy)]
+pub struct RadixDisplay<T> {
+    value: T,
+    alphabet: &'static [u8],
+    /// この基数での`T`の最大桁数
+    len: usize,
+}
+
+impl<T: FormatInteger> RadixDisplay<T> {
+    /// `0-9a-z`を使って`radix`進数で書式化する
+    ///
+    /// `radix`が2〜36でなければパニックする。
+    pub fn new(value: T, radix: u32) -> Self {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in 2..=36: {}",
+            radix
+        );
+        RadixDisplay {
+            value,
+            alphabet: &BASE36_DIGITS[..radix as usize],
+            len: max_radix_len(T::BITS, radix),
+        }
+    }
+
+    /// `alphabet`の文字を数字として、`alphabet.len()`進数で書式化する
+    ///
+    /// `alphabet`が2〜64文字のASCIIでないか、重複があればパニックする。
+    pub fn with_alphabet(value: T, alphabet: &'static [u8]) -> Self {
+        assert!(
+            (2..=64).contains(&alphabet.len()),
+            "alphabet must have 2 to 64 characters"
+        );
+        assert!(alphabet.is_ascii(), "alphabet must be ASCII");
+        for (i, c) in alphabet.iter().enumerate() {
+            assert!(
+                !alphabet[..i].contains(c),
+                "duplicated character in alphabet: {:?}",
+                *c as char
+            );
+        }
+        RadixDisplay {
+            value,
+            alphabet,
+            len: max_radix_len(T::BITS, alphabet.len() as u32),
+        }
+    }
+
+    /// 基数
+    pub fn radix(&self) -> u32 {
+        self.alphabet.len() as u32
+    }
+}
+
+impl<T: FormatInteger> Display for RadixDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 基数ごとの最大桁数だけを使う
+        let mut buf = [0u8; MAX_BITS_LEN];
+        let buf = &mut buf[..self.len];
+        let cur = self.value.write_abs_radix(buf, self.radix(), self.alphabet);
+
+        unsafe {
+            // アルファベットはASCIIに限っている
+            let buf_slice = from_utf8_unchecked(&buf[cur..]);
+            f.pad_integral(self.value.is_nonnegative(), "", buf_slice)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `alphabet`による表記を読む
+    fn decode(s: &str, alphabet: &[u8]) -> i128 {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let mut n: i128 = 0;
+        for c in digits.bytes() {
+            let d = alphabet.iter().position(|&a| a == c).unwrap();
+            n = n * alphabet.len() as i128 - if negative { d as i128 } else { -(d as i128) };
+        }
+        n
+    }
+
+    #[test]
+    fn test_max_radix_len() {
+        assert_eq!(max_radix_len(8, 10), 3);
+        assert_eq!(max_radix_len(64, 10), 20);
+        assert_eq!(max_radix_len(128, 10), 39);
+        assert_eq!(max_radix_len(128, 2), 128);
+        assert_eq!(max_radix_len(64, 36), 13);
+        assert_eq!(max_radix_len(64, 62), 11);
+    }
+
+    #[test]
+    fn test_radix_round_trip() {
+        let vals: [u64; 6] = [0, 1, 35, 36, 1295, u64::MAX];
+        for radix in 2..=36 {
+            for val in vals.iter().copied() {
+                let s = RadixDisplay::new(val, radix).to_string();
+                assert_eq!(u64::from_str_radix(&s, radix).unwrap(), val);
+            }
+            for val in [i64::MIN, -1, i64::MAX].iter().copied() {
+                let s = RadixDisplay::new(val, radix).to_string();
+                assert_eq!(i64::from_str_radix(&s, radix).unwrap(), val);
+            }
+            let s = RadixDisplay::new(u128::MAX, radix).to_string();
+            assert_eq!(s.len(), max_radix_len(128, radix));
+            assert_eq!(u128::from_str_radix(&s, radix).unwrap(), u128::MAX);
+            let s = RadixDisplay::new(i8::MIN, radix).to_string();
+            assert_eq!(i8::from_str_radix(&s, radix).unwrap(), i8::MIN);
+        }
+
+        assert_eq!(RadixDisplay::new(255u8, 16).to_string(), "ff");
+        assert_eq!(format!("{:>+6}", RadixDisplay::new(35i32, 36)), "    +z");
+    }
+
+    #[test]
+    fn test_alphabet_round_trip() {
+        let vals: [i64; 6] = [0, 1, 57, 62, -3843, i64::MIN];
+        for alphabet in [&BASE62_ALPHABET[..], &BASE58_ALPHABET[..]].iter() {
+            for val in vals.iter().copied() {
+                let s = RadixDisplay::with_alphabet(val, alphabet).to_string();
+                assert_eq!(decode(&s, alphabet), i128::from(val));
+            }
+        }
+
+        assert_eq!(
+            RadixDisplay::with_alphabet(61u8, BASE62_ALPHABET).to_string(),
+            "z"
+        );
+        assert_eq!(
+            RadixDisplay::with_alphabet(0u8, BASE58_ALPHABET).to_string(),
+            "1"
+        );
+        assert_eq!(
+            RadixDisplay::with_alphabet(u128::MAX, b"01")
+                .to_string()
+                .len(),
+            128
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicated_alphabet() {
+        RadixDisplay::with_alphabet(0u32, b"0120");
+    }
+}
@@ -0,0 +1,521 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::{bail, Error};
+
+use itoa_example::{
+    decimal_len, parse, FloatDisplay, FormatFloat, FormatInteger, LutDisplay, ParseInteger,
+    SimpleDisplay,
+};
+
+/// 数値から文字列への変換の実装
+///
+/// ベンチマークでは、値の列をカンマ区切りで書き出す時間を計測する。
+pub trait Implementation<T> {
+    /// 実装の名前 (出力の列名に使う)
+    fn name(&self) -> &'static str;
+
+    /// 各値を`"{},"`の形式で`out`に追記する
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>);
+
+    /// 他の実装の出力の検証に使う基準の実装かどうか
+    fn is_reference(&self) -> bool {
+        false
+    }
+}
+
+/// 登録されている全ての実装を返す
+///
+/// 先頭から順にベンチマークの列として出力される。
+/// 基準の実装(`Std`)はちょうど1つ含まれる。
+pub fn implementations<T: FormatInteger>() -> Vec<Box<dyn Implementation<T>>> {
+    vec![
+        Box::new(Simple),
+        Box::new(Std),
+        Box::new(Lut),
+        Box::new(Raw),
+        Box::new(Forward),
+    ]
+}
+
+/// 計測に使う書式トレイト
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatTrait {
+    /// `{}`
+    Display,
+    /// `{:x}`
+    LowerHex,
+    /// `{:X}`
+    UpperHex,
+    /// `{:o}`
+    Octal,
+    /// `{:b}`
+    Binary,
+    /// `{:e}`
+    LowerExp,
+    /// `{:E}`
+    UpperExp,
+}
+
+impl FormatTrait {
+    /// 全ての書式トレイト
+    pub const ALL: [FormatTrait; 7] = [
+        FormatTrait::Display,
+        FormatTrait::LowerHex,
+        FormatTrait::UpperHex,
+        FormatTrait::Octal,
+        FormatTrait::Binary,
+        FormatTrait::LowerExp,
+        FormatTrait::UpperExp,
+    ];
+
+    /// コマンドラインで使う名前
+    pub fn name(self) -> &'static str {
+        match self {
+            FormatTrait::Display => "display",
+            FormatTrait::LowerHex => "lower-hex",
+            FormatTrait::UpperHex => "upper-hex",
+            FormatTrait::Octal => "octal",
+            FormatTrait::Binary => "binary",
+            FormatTrait::LowerExp => "lower-exp",
+            FormatTrait::UpperExp => "upper-exp",
+        }
+    }
+}
+
+impl FromStr for FormatTrait {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match FormatTrait::ALL.iter().find(|t| t.name() == s) {
+            Some(t) => Ok(*t),
+            None => bail!("unknown format trait: {}", s),
+        }
+    }
+}
+
+/// 書式トレイト`format_trait`について計測する実装を返す
+///
+/// `Display`以外は`SimpleDisplay`と標準ライブラリの実装だけを比べる。
+pub fn implementations_for<T: FormatInteger>(
+    format_trait: FormatTrait,
+) -> Vec<Box<dyn Implementation<T>>> {
+    match format_trait {
+        FormatTrait::Display => implementations(),
+        _ => vec![
+            Box::new(SimpleTrait(format_trait)),
+            Box::new(StdTrait(format_trait)),
+        ],
+    }
+}
+
+/// 各値を`"{:x},"`のように`format_trait`の書式で`out`に追記する
+macro_rules! write_with_trait {
+    ($format_trait:expr, $values:expr, $out:expr, $wrap:expr) => {
+        match $format_trait {
+            FormatTrait::Display => {
+                for v in $values.iter() {
+                    write!($out, "{},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::LowerHex => {
+                for v in $values.iter() {
+                    write!($out, "{:x},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::UpperHex => {
+                for v in $values.iter() {
+                    write!($out, "{:X},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::Octal => {
+                for v in $values.iter() {
+                    write!($out, "{:o},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::Binary => {
+                for v in $values.iter() {
+                    write!($out, "{:b},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::LowerExp => {
+                for v in $values.iter() {
+                    write!($out, "{:e},", $wrap(*v)).unwrap();
+                }
+            }
+            FormatTrait::UpperExp => {
+                for v in $values.iter() {
+                    write!($out, "{:E},", $wrap(*v)).unwrap();
+                }
+            }
+        }
+    };
+}
+
+/// `SimpleDisplay`の指定した書式トレイトによる実装
+#[derive(Debug, Clone, Copy)]
+pub struct SimpleTrait(pub FormatTrait);
+
+impl<T: FormatInteger> Implementation<T> for SimpleTrait {
+    fn name(&self) -> &'static str {
+        "Simple"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        write_with_trait!(self.0, values, out, SimpleDisplay);
+    }
+}
+
+/// 標準ライブラリの指定した書式トレイトによる実装
+#[derive(Debug, Clone, Copy)]
+pub struct StdTrait(pub FormatTrait);
+
+impl<T: FormatInteger> Implementation<T> for StdTrait {
+    fn name(&self) -> &'static str {
+        "Std"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        write_with_trait!(self.0, values, out, |v: T| v);
+    }
+
+    fn is_reference(&self) -> bool {
+        true
+    }
+}
+
+/// `SimpleDisplay`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct Simple;
+
+impl<T: FormatInteger> Implementation<T> for Simple {
+    fn name(&self) -> &'static str {
+        "Simple"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            write!(out, "{},", SimpleDisplay(*v)).unwrap();
+        }
+    }
+}
+
+/// 標準ライブラリの`Display`による実装
+///
+/// 浮動小数点数の基準にも使う。
+#[derive(Debug, Clone, Copy)]
+pub struct Std;
+
+impl<T: Copy + Display> Implementation<T> for Std {
+    fn name(&self) -> &'static str {
+        "Std"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            write!(out, "{},", v).unwrap();
+        }
+    }
+
+    fn is_reference(&self) -> bool {
+        true
+    }
+}
+
+/// `LutDisplay`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct Lut;
+
+impl<T: FormatInteger> Implementation<T> for Lut {
+    fn name(&self) -> &'static str {
+        "Lut"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            write!(out, "{},", LutDisplay(*v)).unwrap();
+        }
+    }
+}
+
+/// `SimpleDisplay::append_to`による、`core::fmt`を通さない実装
+#[derive(Debug, Clone, Copy)]
+pub struct Raw;
+
+impl<T: FormatInteger> Implementation<T> for Raw {
+    fn name(&self) -> &'static str {
+        "Raw"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            SimpleDisplay(*v).append_to(out);
+            out.push(b',');
+        }
+    }
+}
+
+/// `SimpleDisplay::append_forward`による、一時バッファを経由しない実装
+#[derive(Debug, Clone, Copy)]
+pub struct Forward;
+
+impl<T: FormatInteger> Implementation<T> for Forward {
+    fn name(&self) -> &'static str {
+        "Forward"
+    }
+
+    fn write_all(&self, values: &[T], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            SimpleDisplay(*v).append_forward(out);
+            out.push(b',');
+        }
+    }
+}
+
+/// 登録されている浮動小数点数の実装を返す
+///
+/// 基準の実装(`Std`)はちょうど1つ含まれる。
+pub fn float_implementations<F: FormatFloat>() -> Vec<Box<dyn Implementation<F>>> {
+    vec![Box::new(Ryu), Box::new(Std)]
+}
+
+/// `FloatDisplay`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct Ryu;
+
+impl<F: FormatFloat> Implementation<F> for Ryu {
+    fn name(&self) -> &'static str {
+        "Ryu"
+    }
+
+    fn write_all(&self, values: &[F], out: &mut Vec<u8>) {
+        for v in values.iter() {
+            write!(out, "{},", FloatDisplay(*v)).unwrap();
+        }
+    }
+}
+
+/// 文字列から整数への変換の実装
+///
+/// ベンチマークでは、カンマ区切りの10進表記の列を解析する時間を計測する。
+pub trait Parser<T> {
+    /// 実装の名前 (出力の列名に使う)
+    fn name(&self) -> &'static str;
+
+    /// カンマ区切りの`input`を解析して`out`に追記する
+    ///
+    /// 解析できなかった値は飛ばす。
+    fn parse_all(&self, input: &str, out: &mut Vec<T>);
+
+    /// 他の実装との比較に使う基準の実装かどうか
+    fn is_reference(&self) -> bool {
+        false
+    }
+}
+
+/// 登録されている全ての解析の実装を返す
+///
+/// 基準の実装(`StdParse`)はちょうど1つ含まれる。
+pub fn parsers<T: ParseInteger + FromStr>() -> Vec<Box<dyn Parser<T>>> {
+    vec![Box::new(Atoi), Box::new(StdParse)]
+}
+
+/// `parse`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct Atoi;
+
+impl<T: ParseInteger> Parser<T> for Atoi {
+    fn name(&self) -> &'static str {
+        "Atoi"
+    }
+
+    fn parse_all(&self, input: &str, out: &mut Vec<T>) {
+        for s in input.as_bytes().split(|&c| c == b',') {
+            if let Ok(v) = parse(s) {
+                out.push(v);
+            }
+        }
+    }
+}
+
+/// 標準ライブラリの`str::parse`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct StdParse;
+
+impl<T: FromStr> Parser<T> for StdParse {
+    fn name(&self) -> &'static str {
+        "Std"
+    }
+
+    fn parse_all(&self, input: &str, out: &mut Vec<T>) {
+        for s in input.split(',') {
+            if let Ok(v) = s.parse() {
+                out.push(v);
+            }
+        }
+    }
+
+    fn is_reference(&self) -> bool {
+        true
+    }
+}
+
+/// `u64`の10進表記の桁数を数える実装
+///
+/// ベンチマークでは、値の列の各桁数を求める時間を計測する。
+pub trait LenCounter {
+    /// 実装の名前 (出力の列名に使う)
+    fn name(&self) -> &'static str;
+
+    /// 各値の桁数を`out`に追記する
+    fn count_all(&self, values: &[u64], out: &mut Vec<u8>);
+
+    /// 他の実装の結果の検証に使う基準の実装かどうか
+    fn is_reference(&self) -> bool {
+        false
+    }
+}
+
+/// 登録されている全ての桁数の実装を返す
+///
+/// 基準の実装(`StdLen`)はちょうど1つ含まれる。
+pub fn len_counters() -> Vec<Box<dyn LenCounter>> {
+    vec![Box::new(TableLen), Box::new(LoopLen), Box::new(StdLen)]
+}
+
+/// `decimal_len`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct TableLen;
+
+impl LenCounter for TableLen {
+    fn name(&self) -> &'static str {
+        "Table"
+    }
+
+    fn count_all(&self, values: &[u64], out: &mut Vec<u8>) {
+        out.extend(values.iter().map(|&v| decimal_len(v) as u8));
+    }
+}
+
+/// 10で割り続ける素朴な実装
+#[derive(Debug, Clone, Copy)]
+pub struct LoopLen;
+
+impl LenCounter for LoopLen {
+    fn name(&self) -> &'static str {
+        "Loop"
+    }
+
+    fn count_all(&self, values: &[u64], out: &mut Vec<u8>) {
+        out.extend(values.iter().map(|&v| {
+            let mut n = v;
+            let mut len = 1;
+            while n >= 10 {
+                n /= 10;
+                len += 1;
+            }
+            len
+        }));
+    }
+}
+
+/// 標準ライブラリの`checked_ilog10`による実装
+#[derive(Debug, Clone, Copy)]
+pub struct StdLen;
+
+impl LenCounter for StdLen {
+    fn name(&self) -> &'static str {
+        "Std"
+    }
+
+    fn count_all(&self, values: &[u64], out: &mut Vec<u8>) {
+        out.extend(
+            values
+                .iter()
+                .map(|v| v.checked_ilog10().map_or(1, |l| l as u8 + 1)),
+        );
+    }
+
+    fn is_reference(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implementations_agree() {
+        let values: Vec<i64> = vec![0, 1, -1, 99, -100, i64::MIN, i64::MAX];
+        let impls = implementations::<i64>();
+        assert_eq!(impls.iter().filter(|i| i.is_reference()).count(), 1);
+
+        let mut expected = Vec::new();
+        Std.write_all(&values, &mut expected);
+
+        for imp in impls.iter() {
+            let mut out = Vec::new();
+            imp.write_all(&values, &mut out);
+            assert_eq!(out, expected, "{}", imp.name());
+        }
+    }
+
+    #[test]
+    fn test_float_implementations_agree() {
+        let values: Vec<f64> = vec![0.0, -0.0, 0.1, 1e21, 5e-324, f64::NAN, f64::NEG_INFINITY];
+        let mut expected = Vec::new();
+        Std.write_all(&values, &mut expected);
+
+        for imp in float_implementations::<f64>().iter() {
+            let mut out = Vec::new();
+            imp.write_all(&values, &mut out);
+            assert_eq!(out, expected, "{}", imp.name());
+        }
+    }
+
+    #[test]
+    fn test_len_counters_agree() {
+        let values: Vec<u64> = vec![0, 9, 10, 99, 100, 1 << 63, u64::MAX];
+        let expected: Vec<u8> = values.iter().map(|v| v.to_string().len() as u8).collect();
+        for counter in len_counters().iter() {
+            let mut out = Vec::new();
+            counter.count_all(&values, &mut out);
+            assert_eq!(out, expected, "{}", counter.name());
+        }
+    }
+
+    #[test]
+    fn test_parsers_agree() {
+        let input = "0,-1,42,-9223372036854775808,x,9223372036854775808";
+        let expected: Vec<i64> = vec![0, -1, 42, i64::MIN];
+        for parser in parsers::<i64>().iter() {
+            let mut out = Vec::new();
+            parser.parse_all(input, &mut out);
+            assert_eq!(out, expected, "{}", parser.name());
+        }
+    }
+
+    #[test]
+    fn test_implementations_for() {
+        let values: Vec<i32> = vec![0, 1, -1, 1250, i32::MIN, i32::MAX];
+        for format_trait in FormatTrait::ALL.iter().copied() {
+            assert_eq!(
+                format_trait.name().parse::<FormatTrait>().unwrap(),
+                format_trait
+            );
+
+            let impls = implementations_for::<i32>(format_trait);
+            let reference = impls.iter().find(|i| i.is_reference()).unwrap();
+            let mut expected = Vec::new();
+            reference.write_all(&values, &mut expected);
+
+            for imp in impls.iter() {
+                let mut out = Vec::new();
+                imp.write_all(&values, &mut out);
+                assert_eq!(out, expected, "{} {}", imp.name(), format_trait.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::Write;
+use std::fs;
+
+use crate::bench::Config;
+use crate::chart::{render_svg, xml_escape};
+use crate::output::{cells, columns, DigitsResult};
+use crate::table::ResultTable;
+
+/// レポートに載せる実行条件
+#[derive(Debug, Clone)]
+pub struct RunInfo {
+    /// 計測した整数型の名前
+    pub int_type: String,
+    pub seed: u64,
+    pub config: Config,
+}
+
+/// 計測結果を1つのHTMLファイルにまとめる
+///
+/// 実行条件、検証結果、グラフ(SVGを埋め込む)、表形式の出力と同じ内容の表を含む。
+pub fn render_html(info: &RunInfo, results: &[DigitsResult]) -> String {
+    let title = format!("itoa-example benchmark ({})", info.int_type);
+    let mut html = String::new();
+
+    writeln!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+        xml_escape(&title)
+    )
+    .unwrap();
+    html.push_str(
+        "<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         table { border-collapse: collapse; font-size: 12px; }\n\
+         th, td { border: 1px solid #ccc; padding: 2px 6px; text-align: right; }\n\
+         th { background: #f4f4f4; }\n\
+         .pass { color: #2a7a2a; font-weight: bold; }\n\
+         .fail { color: #c02020; font-weight: bold; }\n\
+         .scroll { overflow-x: auto; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    writeln!(html, "<h1>{}</h1>", xml_escape(&title)).unwrap();
+
+    // 実行条件
+    let c = &info.config;
+    let budget = match c.time_budget {
+        Some(d) => format!("{} s", d.as_secs_f64()),
+        None => "none".to_string(),
+    };
+    let conditions = [
+        ("Type", info.int_type.clone()),
+        (
+            "Operation",
+            if c.parse {
+                "parse".to_string()
+            } else if c.len {
+                "decimal length".to_string()
+            } else {
+                format!("format ({})", c.format_trait.name())
+            },
+        ),
+        ("Seed", info.seed.to_string()),
+        ("Values per iteration", c.size.to_string()),
+        ("Iterations", c.iter.to_string()),
+        ("Warm-up iterations", c.warmup.to_string()),
+        ("Time budget per digit count", budget),
+        ("Outlier filter", format!("{:?}", c.outliers)),
+        (
+            "Distributions",
+            c.distributions
+                .iter()
+                .map(|d| d.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        (
+            "Input file",
+            match &c.input {
+                Some(input) => format!("{} ({:?})", input.path.display(), input.format),
+                None => "none (random values)".to_string(),
+            },
+        ),
+        ("CPU", cpu_model()),
+        (
+            "OS",
+            format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+        ("rustc", env!("RUSTC_VERSION").to_string()),
+        ("itoa-example", env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    html.push_str("<h2>Configuration</h2>\n<table>\n");
+    for (k, v) in conditions.iter() {
+        writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            xml_escape(k),
+            xml_escape(v)
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+
+    // 検証結果
+    html.push_str("<h2>Verification</h2>\n");
+    let passed = results.iter().all(|r| r.verified());
+    writeln!(
+        html,
+        "<p class=\"{}\">{}</p>",
+        if passed { "pass" } else { "fail" },
+        if passed {
+            "PASS: every output matched the reference implementation"
+        } else {
+            "FAIL: some outputs differ from the reference implementation"
+        }
+    )
+    .unwrap();
+    html.push_str("<table>\n<tr><th>Implementation</th><th>Checked values</th><th>Mismatched iterations</th><th>Result</th></tr>\n");
+    if let Some(first) = results.first() {
+        for (i, imp) in first.impls.iter().enumerate() {
+            let checked: usize = results.iter().map(|r| r.impls[i].checked_values).sum();
+            let mismatched: usize = results.iter().map(|r| r.impls[i].mismatched_iters).sum();
+            let (class, label) = if mismatched == 0 {
+                ("pass", "PASS")
+            } else {
+                ("fail", "FAIL")
+            };
+            writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>",
+                xml_escape(imp.name),
+                checked,
+                mismatched,
+                class,
+                label
+            )
+            .unwrap();
+        }
+    }
+    html.push_str("</table>\n");
+
+    // グラフ
+    html.push_str("<h2>Time versus digit count</h2>\n");
+    html.push_str(&render_svg(&ResultTable::from_results(results), &title));
+
+    // 表
+    html.push_str("<h2>Results</h2>\n<div class=\"scroll\">\n<table>\n<tr>");
+    if let Some(first) = results.first() {
+        let names: Vec<(&str, bool)> = first
+            .impls
+            .iter()
+            .map(|r| (r.name, r.is_reference))
+            .collect();
+        for col in columns(&names).iter() {
+            write!(html, "<th>{}</th>", xml_escape(col)).unwrap();
+        }
+    }
+    html.push_str("</tr>\n");
+    for r in results.iter() {
+        html.push_str("<tr>");
+        for cell in cells(r).iter() {
+            write!(html, "<td>{}</td>", xml_escape(cell)).unwrap();
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</div>\n</body>\n</html>\n");
+
+    html
+}
+
+/// `/proc/cpuinfo`からCPUの型番を読む
+///
+/// 読めない場合は`unknown`を返す。
+pub fn cpu_model() -> String {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|l| l.starts_with("model name"))
+                .and_then(|l| l.split(':').nth(1))
+                .map(|m| m.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::Distribution;
+    use crate::output::ImplResult;
+    use crate::registry::FormatTrait;
+    use itoa_example::{stats, OutlierFilter};
+
+    #[test]
+    fn test_render_html() {
+        let samples = vec![1.0, 2.0];
+        let result = DigitsResult {
+            label: "1".to_string(),
+            value_min: "1".to_string(),
+            value_max: "9".to_string(),
+            impls: vec![ImplResult {
+                name: "Std",
+                is_reference: true,
+                samples: samples.clone(),
+                stats: stats(&samples),
+                verdict: None,
+                checked_values: 20,
+                mismatched_iters: 0,
+                values_per_iter: 10,
+                bytes_per_iter: 20.0,
+            }],
+        };
+        let info = RunInfo {
+            int_type: "u64".to_string(),
+            seed: 42,
+            config: Config {
+                size: 10,
+                iter: 2,
+                min_digits: 1,
+                max_digits: 1,
+                time_budget: None,
+                warmup: 0,
+                outliers: OutlierFilter::None,
+                distributions: vec![Distribution::Digits],
+                input: None,
+                format_trait: FormatTrait::Display,
+                parse: false,
+                len: false,
+                exp_step: 10,
+            },
+        };
+
+        let html = render_html(&info, &[result]);
+        assert!(html.contains("<svg"));
+        assert!(html.contains("<td>42</td>"));
+        assert!(html.contains("PASS: every output"));
+        assert!(html.contains("<th>StdAvg</th>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
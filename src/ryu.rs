@@ -0,0 +1,477 @@
+//! Ryuアルゴリズムによる浮動小数点数の最短表記の計算
+//!
+//! Ulf Adams, "Ryū: fast float-to-string conversion" (PLDI 2018) に従う。
+//! 5の累乗の表は、初めて使うときに多倍長整数で計算する。
+
+use std::sync::OnceLock;
+
+const DOUBLE_MANTISSA_BITS: u32 = 52;
+const DOUBLE_BIAS: i32 = 1023;
+const DOUBLE_POW5_INV_BITCOUNT: i32 = 125;
+const DOUBLE_POW5_BITCOUNT: i32 = 125;
+const DOUBLE_POW5_INV_TABLE_SIZE: usize = 342;
+const DOUBLE_POW5_TABLE_SIZE: usize = 326;
+
+const FLOAT_MANTISSA_BITS: u32 = 23;
+const FLOAT_BIAS: i32 = 127;
+const FLOAT_POW5_INV_BITCOUNT: i32 = 59;
+const FLOAT_POW5_BITCOUNT: i32 = 61;
+const FLOAT_POW5_INV_TABLE_SIZE: usize = 31;
+const FLOAT_POW5_TABLE_SIZE: usize = 48;
+
+/// 5の累乗とその逆数の上位ビットの表
+struct Tables {
+    /// `2^(bitlen(5^i) - 1 + 125) / 5^i + 1`
+    double_pow5_inv: Vec<u128>,
+    /// `5^i`の上位125ビット
+    double_pow5: Vec<u128>,
+    /// `2^(bitlen(5^i) - 1 + 59) / 5^i + 1`
+    float_pow5_inv: Vec<u64>,
+    /// `5^i`の上位61ビット
+    float_pow5: Vec<u64>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| Tables {
+        double_pow5_inv: pow5_inv_table(DOUBLE_POW5_INV_TABLE_SIZE, DOUBLE_POW5_INV_BITCOUNT),
+        double_pow5: pow5_table(DOUBLE_POW5_TABLE_SIZE, DOUBLE_POW5_BITCOUNT),
+        float_pow5_inv: pow5_inv_table(FLOAT_POW5_INV_TABLE_SIZE, FLOAT_POW5_INV_BITCOUNT)
+            .into_iter()
+            .map(|v| v as u64)
+            .collect(),
+        float_pow5: pow5_table(FLOAT_POW5_TABLE_SIZE, FLOAT_POW5_BITCOUNT)
+            .into_iter()
+            .map(|v| v as u64)
+            .collect(),
+    })
+}
+
+/// `5^0`から`5^(size - 1)`までの上位`bits`ビット
+fn pow5_table(size: usize, bits: i32) -> Vec<u128> {
+    let mut pow = Big::one();
+    (0..size)
+        .map(|_| {
+            let len = pow.bit_len() as i32;
+            let v = if len > bits {
+                pow.shr_u128((len - bits) as u32)
+            } else {
+                pow.shr_u128(0) << (bits - len)
+            };
+            pow.mul_small(5);
+            v
+        })
+        .collect()
+}
+
+/// `5^0`から`5^(size - 1)`までの逆数を`bitlen(5^i) - 1 + bits`ビット左にずらしたもの(切り上げ)
+fn pow5_inv_table(size: usize, bits: i32) -> Vec<u128> {
+    let mut pow = Big::one();
+    (0..size)
+        .map(|_| {
+            let j = pow.bit_len() - 1 + bits as u32;
+            let v = div_pow2(j, &pow) + 1;
+            pow.mul_small(5);
+            v
+        })
+        .collect()
+}
+
+/// 表の計算に使う多倍長の非負整数 (下位から32ビットずつ)
+#[derive(Debug, Clone)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn one() -> Big {
+        Big(vec![1])
+    }
+
+    fn mul_small(&mut self, m: u32) {
+        let mut carry = 0u64;
+        for d in self.0.iter_mut() {
+            let v = u64::from(*d) * u64::from(m) + carry;
+            *d = v as u32;
+            carry = v >> 32;
+        }
+        if carry > 0 {
+            self.0.push(carry as u32);
+        }
+    }
+
+    /// 上位の0の要素を取り除く
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn bit_len(&self) -> u32 {
+        match self.0.last() {
+            Some(&top) => (self.0.len() as u32 - 1) * 32 + (32 - top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    fn bit(&self, i: u32) -> bool {
+        match self.0.get((i / 32) as usize) {
+            Some(d) => d >> (i % 32) & 1 == 1,
+            None => false,
+        }
+    }
+
+    /// `self >> shift`の下位128ビット
+    fn shr_u128(&self, shift: u32) -> u128 {
+        (0..128)
+            .filter(|&i| self.bit(shift + i))
+            .fold(0, |acc, i| acc | 1 << i)
+    }
+
+    /// 1ビット左にずらし、最下位ビットを`b`にする
+    fn shl1_or(&mut self, b: bool) {
+        let mut carry = u32::from(b);
+        for d in self.0.iter_mut() {
+            let next = *d >> 31;
+            *d = *d << 1 | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            self.0.push(carry);
+        }
+    }
+
+    fn ge(&self, other: &Big) -> bool {
+        if self.0.len() != other.0.len() {
+            return self.0.len() > other.0.len();
+        }
+        for (a, b) in self.0.iter().rev().zip(other.0.iter().rev()) {
+            if a != b {
+                return a > b;
+            }
+        }
+        true
+    }
+
+    /// `self -= other` (`self >= other`であること)
+    fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0i64;
+        for (i, d) in self.0.iter_mut().enumerate() {
+            let v = i64::from(*d) - i64::from(other.0.get(i).copied().unwrap_or(0)) - borrow;
+            *d = v as u32;
+            borrow = if v < 0 { 1 } else { 0 };
+        }
+        self.trim();
+    }
+}
+
+/// `2^k / d`の商 (128ビットに収まること)
+fn div_pow2(k: u32, d: &Big) -> u128 {
+    let mut r = Big(Vec::new());
+    let mut q = 0u128;
+    for i in (0..=k).rev() {
+        r.shl1_or(i == k);
+        q <<= 1;
+        if r.ge(d) {
+            r.sub_assign(d);
+            q |= 1;
+        }
+    }
+    q
+}
+
+/// `ceil(log2(5^e))` (`e = 0`の場合は1)
+fn pow5bits(e: i32) -> i32 {
+    ((e as u32 * 1_217_359) >> 19) as i32 + 1
+}
+
+/// `floor(log10(2^e))`
+fn log10_pow2(e: i32) -> u32 {
+    (e as u32 * 78_913) >> 18
+}
+
+/// `floor(log10(5^e))`
+fn log10_pow5(e: i32) -> u32 {
+    (e as u32 * 732_923) >> 20
+}
+
+fn pow5_factor(mut v: u64) -> u32 {
+    let mut count = 0;
+    while v.is_multiple_of(5) {
+        v /= 5;
+        count += 1;
+    }
+    count
+}
+
+fn multiple_of_power_of_5(v: u64, p: u32) -> bool {
+    pow5_factor(v) >= p
+}
+
+/// `(m * mul) >> j` (`j >= 64`)
+fn mul_shift_64(m: u64, mul: u128, j: i32) -> u64 {
+    let b0 = u128::from(m) * (mul as u64 as u128);
+    let b2 = u128::from(m) * (mul >> 64);
+    (((b0 >> 64) + b2) >> (j - 64)) as u64
+}
+
+/// `(m * factor) >> shift` (`shift >= 32`)
+fn mul_shift_32(m: u32, factor: u64, shift: i32) -> u32 {
+    let bits0 = u64::from(m) * (factor as u32 as u64);
+    let bits1 = u64::from(m) * (factor >> 32);
+    (((bits0 >> 32) + bits1) >> (shift - 32)) as u32
+}
+
+/// `f64`の仮数部と指数部から、最短で元の値に戻る10進表記`(仮数, 指数)`を求める
+///
+/// 有限で0でない値についてのみ使える。
+pub(crate) fn d2d(ieee_mantissa: u64, ieee_exponent: u32) -> (u64, i32) {
+    let t = tables();
+    let (e2, m2) = if ieee_exponent == 0 {
+        (
+            1 - DOUBLE_BIAS - DOUBLE_MANTISSA_BITS as i32 - 2,
+            ieee_mantissa,
+        )
+    } else {
+        (
+            ieee_exponent as i32 - DOUBLE_BIAS - DOUBLE_MANTISSA_BITS as i32 - 2,
+            (1 << DOUBLE_MANTISSA_BITS) | ieee_mantissa,
+        )
+    };
+    let accept_bounds = m2 & 1 == 0;
+
+    // 値とその前後の浮動小数点数との中点を 4 倍したもの
+    let mv = 4 * m2;
+    let mm_shift = u64::from(ieee_mantissa != 0 || ieee_exponent <= 1);
+
+    let mut vm_is_trailing_zeros = false;
+    let e10;
+    let (mut vr, mut vp, mut vm);
+    if e2 >= 0 {
+        let q = log10_pow2(e2) - u32::from(e2 > 3);
+        e10 = q as i32;
+        let k = DOUBLE_POW5_INV_BITCOUNT + pow5bits(q as i32) - 1;
+        let i = -e2 + q as i32 + k;
+        let mul = t.double_pow5_inv[q as usize];
+        vr = mul_shift_64(mv, mul, i);
+        vp = mul_shift_64(mv + 2, mul, i);
+        vm = mul_shift_64(mv - 1 - mm_shift, mul, i);
+        if q <= 21 && mv % 5 != 0 {
+            if accept_bounds {
+                vm_is_trailing_zeros = multiple_of_power_of_5(mv - 1 - mm_shift, q);
+            } else {
+                vp -= u64::from(multiple_of_power_of_5(mv + 2, q));
+            }
+        }
+    } else {
+        let q = log10_pow5(-e2) - u32::from(-e2 > 1);
+        e10 = q as i32 + e2;
+        let i = -e2 - q as i32;
+        let k = pow5bits(i) - DOUBLE_POW5_BITCOUNT;
+        let j = q as i32 - k;
+        let mul = t.double_pow5[i as usize];
+        vr = mul_shift_64(mv, mul, j);
+        vp = mul_shift_64(mv + 2, mul, j);
+        vm = mul_shift_64(mv - 1 - mm_shift, mul, j);
+        if q <= 1 {
+            if accept_bounds {
+                vm_is_trailing_zeros = mm_shift == 1;
+            } else {
+                vp -= 1;
+            }
+        }
+    }
+
+    // 区間 (vm, vp) に収まる限り下の桁を落とす
+    let mut removed = 0;
+    let output = if vm_is_trailing_zeros {
+        let mut last_removed_digit = 0;
+        while vp / 10 > vm / 10 {
+            vm_is_trailing_zeros &= vm % 10 == 0;
+            last_removed_digit = (vr % 10) as u8;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        if vm_is_trailing_zeros {
+            while vm % 10 == 0 {
+                last_removed_digit = (vr % 10) as u8;
+                vr /= 10;
+                vm /= 10;
+                removed += 1;
+            }
+        }
+        // ちょうど中間の場合も、標準ライブラリに合わせて切り上げる
+        vr + u64::from(
+            (vr == vm && (!accept_bounds || !vm_is_trailing_zeros)) || last_removed_digit >= 5,
+        )
+    } else {
+        let mut round_up = false;
+        if vp / 100 > vm / 100 {
+            round_up = vr % 100 >= 50;
+            vr /= 100;
+            vp /= 100;
+            vm /= 100;
+            removed += 2;
+        }
+        while vp / 10 > vm / 10 {
+            round_up = vr % 10 >= 5;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        vr + u64::from(vr == vm || round_up)
+    };
+
+    (output, e10 + removed)
+}
+
+/// `f32`の仮数部と指数部から、最短で元の値に戻る10進表記`(仮数, 指数)`を求める
+///
+/// 有限で0でない値についてのみ使える。
+pub(crate) fn f2d(ieee_mantissa: u32, ieee_exponent: u32) -> (u32, i32) {
+    let t = tables();
+    let (e2, m2) = if ieee_exponent == 0 {
+        (
+            1 - FLOAT_BIAS - FLOAT_MANTISSA_BITS as i32 - 2,
+            ieee_mantissa,
+        )
+    } else {
+        (
+            ieee_exponent as i32 - FLOAT_BIAS - FLOAT_MANTISSA_BITS as i32 - 2,
+            (1 << FLOAT_MANTISSA_BITS) | ieee_mantissa,
+        )
+    };
+    let accept_bounds = m2 & 1 == 0;
+
+    let mv = 4 * m2;
+    let mp = 4 * m2 + 2;
+    let mm_shift = u32::from(ieee_mantissa != 0 || ieee_exponent <= 1);
+    let mm = 4 * m2 - 1 - mm_shift;
+
+    let mul_pow5_inv = |m: u32, q: u32, j: i32| mul_shift_32(m, t.float_pow5_inv[q as usize], j);
+    let mul_pow5 = |m: u32, i: u32, j: i32| mul_shift_32(m, t.float_pow5[i as usize], j);
+
+    let mut vm_is_trailing_zeros = false;
+    let mut last_removed_digit = 0u8;
+    let e10;
+    let (mut vr, mut vp, mut vm);
+    if e2 >= 0 {
+        let q = log10_pow2(e2);
+        e10 = q as i32;
+        let k = FLOAT_POW5_INV_BITCOUNT + pow5bits(q as i32) - 1;
+        let i = -e2 + q as i32 + k;
+        vr = mul_pow5_inv(mv, q, i);
+        vp = mul_pow5_inv(mp, q, i);
+        vm = mul_pow5_inv(mm, q, i);
+        if q != 0 && (vp - 1) / 10 <= vm / 10 {
+            // 落とす桁の最後の1桁を別に求める
+            let l = FLOAT_POW5_INV_BITCOUNT + pow5bits(q as i32 - 1) - 1;
+            last_removed_digit = (mul_pow5_inv(mv, q - 1, -e2 + q as i32 - 1 + l) % 10) as u8;
+        }
+        if q <= 9 && mv % 5 != 0 {
+            if accept_bounds {
+                vm_is_trailing_zeros = multiple_of_power_of_5(u64::from(mm), q);
+            } else {
+                vp -= u32::from(multiple_of_power_of_5(u64::from(mp), q));
+            }
+        }
+    } else {
+        let q = log10_pow5(-e2);
+        e10 = q as i32 + e2;
+        let i = -e2 - q as i32;
+        let k = pow5bits(i) - FLOAT_POW5_BITCOUNT;
+        let j = q as i32 - k;
+        vr = mul_pow5(mv, i as u32, j);
+        vp = mul_pow5(mp, i as u32, j);
+        vm = mul_pow5(mm, i as u32, j);
+        if q != 0 && (vp - 1) / 10 <= vm / 10 {
+            let j = q as i32 - 1 - (pow5bits(i + 1) - FLOAT_POW5_BITCOUNT);
+            last_removed_digit = (mul_pow5(mv, (i + 1) as u32, j) % 10) as u8;
+        }
+        if q <= 1 {
+            if accept_bounds {
+                vm_is_trailing_zeros = mm_shift == 1;
+            } else {
+                vp -= 1;
+            }
+        }
+    }
+
+    let mut removed = 0;
+    let output = if vm_is_trailing_zeros {
+        while vp / 10 > vm / 10 {
+            vm_is_trailing_zeros &= vm % 10 == 0;
+            last_removed_digit = (vr % 10) as u8;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        if vm_is_trailing_zeros {
+            while vm % 10 == 0 {
+                last_removed_digit = (vr % 10) as u8;
+                vr /= 10;
+                vm /= 10;
+                removed += 1;
+            }
+        }
+        vr + u32::from(
+            (vr == vm && (!accept_bounds || !vm_is_trailing_zeros)) || last_removed_digit >= 5,
+        )
+    } else {
+        while vp / 10 > vm / 10 {
+            last_removed_digit = (vr % 10) as u8;
+            vr /= 10;
+            vp /= 10;
+            vm /= 10;
+            removed += 1;
+        }
+        vr + u32::from(vr == vm || last_removed_digit >= 5)
+    };
+
+    (output, e10 + removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        let t = tables();
+        // 5^0 = 1 を125ビット目に合わせたもの
+        assert_eq!(t.double_pow5[0], 1 << 124);
+        // 5^1 = 0b101
+        assert_eq!(t.double_pow5[1], 5 << 122);
+        // 2^125 / 1 + 1
+        assert_eq!(t.double_pow5_inv[0], (1 << 125) + 1);
+        assert_eq!(t.float_pow5[0], 1 << 60);
+        assert_eq!(t.float_pow5_inv[1], (1u64 << 61) / 5 + 1);
+    }
+
+    #[test]
+    fn test_d2d() {
+        let decode = |v: f64| {
+            let bits = v.to_bits();
+            d2d(bits & ((1 << 52) - 1), (bits >> 52) as u32 & 0x7ff)
+        };
+        assert_eq!(decode(1.0), (1, 0));
+        assert_eq!(decode(0.1), (1, -1));
+        assert_eq!(decode(123.456), (123456, -3));
+        assert_eq!(decode(5e-324), (5, -324));
+        assert_eq!(decode(f64::MAX), (17976931348623157, 292));
+    }
+
+    #[test]
+    fn test_f2d() {
+        let decode = |v: f32| {
+            let bits = v.to_bits();
+            f2d(bits & ((1 << 23) - 1), (bits >> 23) & 0xff)
+        };
+        assert_eq!(decode(1.0), (1, 0));
+        assert_eq!(decode(0.1), (1, -1));
+        assert_eq!(decode(1e-45), (1, -45));
+        assert_eq!(decode(f32::MAX), (34028235, 31));
+    }
+}
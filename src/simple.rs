@@ -0,0 +1,238 @@
+use std::fmt::{self, Binary, Display, LowerExp, LowerHex, Octal, UpperExp, UpperHex};
+use std::str::from_utf8_unchecked;
+
+use crate::exp::fmt_exp;
+use crate::integer::{FormatInteger, MAX_BITS_LEN};
+
+/// `u64`の10進表記の最大桁数
+pub const U64_MAX_LEN: usize = <u64 as FormatInteger>::MAX_LEN;
+
+/// 素朴なitoa実装のラッパー型
+///
+/// `FormatInteger`を実装した任意の整数型を包める。
+#[derive(Debug, Clone, Copy)]
+pub struct SimpleDisplay<T>(pub T);
+
+impl<T: FormatInteger> Display for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = T::new_buffer();
+        let buf = buf.as_mut();
+        let cur = self.0.write_abs(buf);
+
+        unsafe {
+            let buf_slice = from_utf8_unchecked(&buf[cur..]);
+            f.pad_integral(self.0.is_nonnegative(), "", buf_slice)
+        }
+    }
+}
+
+impl<T: FormatInteger> SimpleDisplay<T> {
+    /// `core::fmt`を通さずに10進表記を`buf`へ書き込み、書き込んだ部分を返す
+    ///
+    /// 書式指定(幅や`+`など)は扱わない。返す`&str`は常にASCIIの数字と`-`だけからなる。
+    pub fn write_to(self, buf: &mut T::StrBuffer) -> &str {
+        let buf = buf.as_mut();
+        let mut cur = self.0.write_abs(buf);
+        if !self.0.is_nonnegative() {
+            cur -= 1;
+            buf[cur] = b'-';
+        }
+
+        // FormatInteger は封印されていて、write_abs が書くのはASCIIの数字だけ
+        unsafe { from_utf8_unchecked(&buf[cur..]) }
+    }
+
+    /// `core::fmt`を通さずに10進表記を`out`に追記する
+    pub fn append_to(self, out: &mut Vec<u8>) {
+        let mut buf = T::new_str_buffer();
+        out.extend_from_slice(self.write_to(&mut buf).as_bytes());
+    }
+
+    /// `append_to`と同じ内容を、先に桁数を求めて`out`の空き容量へ直接書き込む
+    ///
+    /// 一時バッファからのコピーがないので、`append_to`との差がコピーのコストにあたる。
+    pub fn append_forward(self, out: &mut Vec<u8>) {
+        let sign = usize::from(!self.0.is_nonnegative());
+        let len = sign + self.0.abs_len();
+        out.reserve(len);
+
+        let spare = &mut out.spare_capacity_mut()[..len];
+        if sign == 1 {
+            spare[0].write(b'-');
+        }
+        self.0.write_abs_exact(&mut spare[sign..]);
+
+        // FormatInteger は封印されていて、クレート内の write_abs_exact は buf の全ての要素を
+        // 書き込むので、len バイトを初期化済みとして扱える
+        unsafe { out.set_len(out.len() + len) }
+    }
+}
+
+/// 小文字の16進数字
+const LOWER_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// 大文字の16進数字
+const UPPER_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// `2^shift`進表記を書式化する
+///
+/// 負の値も標準ライブラリと同じく2の補数表現で書く。`{:#x}`の場合は`prefix`を付ける。
+fn fmt_bits<T: FormatInteger>(
+    v: T,
+    f: &mut fmt::Formatter<'_>,
+    shift: u32,
+    digits: &[u8; 16],
+    prefix: &str,
+) -> fmt::Result {
+    let mut buf = [0u8; MAX_BITS_LEN];
+    let cur = v.write_bits(&mut buf, shift, digits);
+
+    unsafe {
+        let buf_slice = from_utf8_unchecked(&buf[cur..]);
+        f.pad_integral(true, prefix, buf_slice)
+    }
+}
+
+impl<T: FormatInteger> LowerHex for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bits(self.0, f, 4, LOWER_DIGITS, "0x")
+    }
+}
+
+impl<T: FormatInteger> UpperHex for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bits(self.0, f, 4, UPPER_DIGITS, "0x")
+    }
+}
+
+impl<T: FormatInteger> Octal for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bits(self.0, f, 3, LOWER_DIGITS, "0o")
+    }
+}
+
+impl<T: FormatInteger> Binary for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_bits(self.0, f, 1, LOWER_DIGITS, "0b")
+    }
+}
+
+impl<T: FormatInteger> LowerExp for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_exp(self.0, f, false)
+    }
+}
+
+impl<T: FormatInteger> UpperExp for SimpleDisplay<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_exp(self.0, f, true)
+    }
+}
+
+/// `n`の10進表記を`buf`の末尾に詰めて書き込み、先頭の位置を返す
+///
+/// 書き込まれた数字列は`buf[cur..]`で取り出せる。
+pub fn write_digits(n: u64, buf: &mut [u8; U64_MAX_LEN]) -> usize {
+    n.write_abs(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_display() {
+        let vals: [u64; 6] = [0, 1, 9, 10, 11, 18446744073709551615];
+
+        for val in vals.iter().copied() {
+            let to_be = format!("{}", val);
+            let actual = format!("{}", SimpleDisplay(val));
+            assert_eq!(actual, to_be);
+        }
+    }
+
+    #[test]
+    fn test_simple_display_signed() {
+        let vals: [i64; 11] = [
+            0,
+            1,
+            -1,
+            -9,
+            -10,
+            i64::from(i8::MIN),
+            i64::from(i8::MAX),
+            i64::from(i16::MIN),
+            i64::from(i32::MIN),
+            i64::MIN,
+            i64::MAX,
+        ];
+
+        for val in vals.iter().copied() {
+            let to_be = format!("{}", val);
+            let actual = format!("{}", SimpleDisplay(val));
+            assert_eq!(actual, to_be);
+        }
+
+        assert_eq!(format!("{:+}", SimpleDisplay(5i64)), "+5");
+        assert_eq!(format!("{:05}", SimpleDisplay(-5i64)), "-0005");
+        assert_eq!(format!("{}", SimpleDisplay(i8::MIN)), "-128");
+        assert_eq!(format!("{}", SimpleDisplay(u16::MAX)), "65535");
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut buf = [0u8; 20];
+        assert_eq!(
+            SimpleDisplay(u64::MAX).write_to(&mut buf),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            SimpleDisplay(i64::MIN).write_to(&mut buf),
+            "-9223372036854775808"
+        );
+        assert_eq!(SimpleDisplay(0u64).write_to(&mut buf), "0");
+
+        let mut buf = [0u8; 4];
+        assert_eq!(SimpleDisplay(i8::MIN).write_to(&mut buf), "-128");
+        assert!(std::str::from_utf8(&buf).is_ok());
+
+        let mut out = b"x=".to_vec();
+        SimpleDisplay(i128::MIN).append_to(&mut out);
+        SimpleDisplay(7u16).append_to(&mut out);
+        assert_eq!(out, format!("x={}7", i128::MIN).into_bytes());
+
+        let mut out = b"x=".to_vec();
+        SimpleDisplay(i128::MIN).append_forward(&mut out);
+        SimpleDisplay(0u8).append_forward(&mut out);
+        SimpleDisplay(-7i32).append_forward(&mut out);
+        assert_eq!(out, format!("x={}0-7", i128::MIN).into_bytes());
+    }
+
+    #[test]
+    fn test_simple_display_bits() {
+        assert_eq!(format!("{:x}", SimpleDisplay(255u8)), "ff");
+        assert_eq!(format!("{:X}", SimpleDisplay(0xabcdu16)), "ABCD");
+        assert_eq!(format!("{:o}", SimpleDisplay(8u32)), "10");
+        assert_eq!(format!("{:b}", SimpleDisplay(5u64)), "101");
+        assert_eq!(format!("{:x}", SimpleDisplay(0u64)), "0");
+        assert_eq!(format!("{:x}", SimpleDisplay(-1i8)), "ff");
+        assert_eq!(format!("{:#010x}", SimpleDisplay(-1i8)), "0x000000ff");
+        assert_eq!(
+            format!("{:#b}", SimpleDisplay(i128::MIN)),
+            format!("{:#b}", i128::MIN)
+        );
+        assert_eq!(format!("{:#^#12X}", SimpleDisplay(255)), "####0xFF####");
+    }
+
+    #[test]
+    fn test_write_digits() {
+        let mut buf = [0u8; U64_MAX_LEN];
+
+        let cur = write_digits(0, &mut buf);
+        assert_eq!(&buf[cur..], b"0");
+
+        let cur = write_digits(u64::MAX, &mut buf);
+        assert_eq!(cur, 0);
+        assert_eq!(&buf[cur..], b"18446744073709551615");
+    }
+}
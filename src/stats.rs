@@ -0,0 +1,261 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Error};
+
+/// 計測結果の統計値
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    /// 計測値の個数
+    pub n: usize,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    /// 標本標準偏差 (計測値が1個の場合は0)
+    pub std_dev: f64,
+    /// 5パーセンタイル
+    pub p5: f64,
+    /// 95パーセンタイル
+    pub p95: f64,
+    /// 平均の95%信頼区間の下限 (t分布による)
+    pub ci_low: f64,
+    /// 平均の95%信頼区間の上限 (t分布による)
+    pub ci_high: f64,
+}
+
+/// 計測値の列から統計値を計算する
+///
+/// `vs`が空の場合はパニックする。
+pub fn stats(vs: &[f64]) -> Stats {
+    assert!(!vs.is_empty(), "no samples");
+
+    let mut sorted = vs.to_vec();
+    sorted.sort_by(|x, y| x.partial_cmp(y).unwrap());
+
+    let n = vs.len();
+    let avg = vs.iter().copied().sum::<f64>() / (n as f64);
+    let std_dev = if n > 1 {
+        let var = vs.iter().map(|v| (v - avg) * (v - avg)).sum::<f64>() / ((n - 1) as f64);
+        var.sqrt()
+    } else {
+        0.0
+    };
+    let half_width = if n > 1 {
+        t_critical_95((n - 1) as f64) * std_dev / (n as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    Stats {
+        n,
+        avg,
+        min: sorted[0],
+        max: sorted[n - 1],
+        median: percentile(&sorted, 50.0),
+        std_dev,
+        p5: percentile(&sorted, 5.0),
+        p95: percentile(&sorted, 95.0),
+        ci_low: avg - half_width,
+        ci_high: avg + half_width,
+    }
+}
+
+/// ソート済みの列の`p`パーセンタイルを線形補間で求める
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * ((sorted.len() - 1) as f64);
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let frac = rank - (lower as f64);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * frac
+}
+
+/// 外れ値の除去方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlierFilter {
+    /// 除去しない
+    None,
+    /// 四分位範囲の1.5倍より外側を除去する
+    Iqr,
+    /// 中央値からの距離が中央絶対偏差(正規分布の標準偏差に換算)の3倍を超えるものを除去する
+    Mad,
+}
+
+impl FromStr for OutlierFilter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(OutlierFilter::None),
+            "iqr" => Ok(OutlierFilter::Iqr),
+            "mad" => Ok(OutlierFilter::Mad),
+            _ => bail!("unknown outlier filter: {}", s),
+        }
+    }
+}
+
+/// 外れ値を除去した計測値の列を返す
+///
+/// 散らばりの尺度(四分位範囲または中央絶対偏差)が0の場合は何も除去しない。
+pub fn reject_outliers(vs: &[f64], filter: OutlierFilter) -> Vec<f64> {
+    if vs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = vs.to_vec();
+    sorted.sort_by(|x, y| x.partial_cmp(y).unwrap());
+
+    let (low, high) = match filter {
+        OutlierFilter::None => return vs.to_vec(),
+        OutlierFilter::Iqr => {
+            let q1 = percentile(&sorted, 25.0);
+            let q3 = percentile(&sorted, 75.0);
+            let iqr = q3 - q1;
+            if iqr == 0.0 {
+                return vs.to_vec();
+            }
+            (q1 - 1.5 * iqr, q3 + 1.5 * iqr)
+        }
+        OutlierFilter::Mad => {
+            let median = percentile(&sorted, 50.0);
+            let mut devs: Vec<f64> = vs.iter().map(|v| (v - median).abs()).collect();
+            devs.sort_by(|x, y| x.partial_cmp(y).unwrap());
+            // 正規分布の標準偏差に換算する係数
+            let mad = 1.4826 * percentile(&devs, 50.0);
+            if mad == 0.0 {
+                return vs.to_vec();
+            }
+            (median - 3.0 * mad, median + 3.0 * mad)
+        }
+    };
+
+    vs.iter()
+        .copied()
+        .filter(|&v| low <= v && v <= high)
+        .collect()
+}
+
+/// 基準の実装と比べた判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// 有意に速い
+    Faster,
+    /// 有意に遅い
+    Slower,
+    /// 有意な差はない
+    Same,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Verdict::Faster => "faster",
+            Verdict::Slower => "slower",
+            Verdict::Same => "same",
+        };
+        f.pad(s)
+    }
+}
+
+/// `target`の計測時間が`reference`と有意水準5%で異なるかをWelchのt検定で判定する
+///
+/// どちらかの計測値が1個しかない場合は`Verdict::Same`とする。
+pub fn compare(target: &Stats, reference: &Stats) -> Verdict {
+    if target.n < 2 || reference.n < 2 {
+        return Verdict::Same;
+    }
+
+    let va = target.std_dev * target.std_dev / (target.n as f64);
+    let vb = reference.std_dev * reference.std_dev / (reference.n as f64);
+    let diff = target.avg - reference.avg;
+
+    let significant = if va + vb == 0.0 {
+        diff != 0.0
+    } else {
+        let t = diff / (va + vb).sqrt();
+        let df = (va + vb) * (va + vb)
+            / (va * va / ((target.n - 1) as f64) + vb * vb / ((reference.n - 1) as f64));
+        t.abs() > t_critical_95(df)
+    };
+
+    if !significant {
+        Verdict::Same
+    } else if diff < 0.0 {
+        Verdict::Faster
+    } else {
+        Verdict::Slower
+    }
+}
+
+/// 自由度`df`のt分布の両側95%点
+///
+/// 自由度30までは表を引き、それより大きい場合はCornish-Fisher展開で近似する。
+/// 自由度の小数部は切り捨てる(保守的な側に倒す)。
+fn t_critical_95(df: f64) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+
+    let df = df.floor().max(1.0);
+    if df <= 30.0 {
+        return TABLE[df as usize - 1];
+    }
+
+    let z = 1.959_963_984_540_054f64;
+    let z3 = z.powi(3);
+    let z5 = z.powi(5);
+    let z7 = z.powi(7);
+    z + (z3 + z) / (4.0 * df)
+        + (5.0 * z5 + 16.0 * z3 + 3.0 * z) / (96.0 * df * df)
+        + (3.0 * z7 + 19.0 * z5 + 17.0 * z3 - 15.0 * z) / (384.0 * df * df * df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let s = stats(&[2.0, 1.0, 3.0]);
+        assert_eq!(s.min, 1.0);
+        assert_eq!(s.max, 3.0);
+        assert_eq!(s.avg, 2.0);
+        assert_eq!(s.median, 2.0);
+        assert_eq!(s.std_dev, 1.0);
+        assert!((s.p5 - 1.1).abs() < 1e-12);
+        assert!((s.p95 - 2.9).abs() < 1e-12);
+        assert!((s.ci_high - (2.0 + 4.303 / 3f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_t_critical_95() {
+        assert_eq!(t_critical_95(10.7), 2.228);
+        // 自由度40, 120 の真値は 2.021, 1.980
+        assert!((t_critical_95(40.0) - 2.021).abs() < 1e-3);
+        assert!((t_critical_95(120.0) - 1.980).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_reject_outliers() {
+        let vs = [1.0, 1.1, 0.9, 1.0, 1.05, 0.95, 10.0];
+        assert_eq!(reject_outliers(&vs, OutlierFilter::None).len(), 7);
+        assert_eq!(reject_outliers(&vs, OutlierFilter::Iqr).len(), 6);
+        assert_eq!(reject_outliers(&vs, OutlierFilter::Mad).len(), 6);
+        assert_eq!(
+            reject_outliers(&[1.0, 1.0, 1.0], OutlierFilter::Mad).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let fast = stats(&[1.0, 1.1, 0.9, 1.0]);
+        let slow = stats(&[2.0, 2.1, 1.9, 2.0]);
+        let noisy = stats(&[0.5, 1.5, 2.5, 1.0]);
+        assert_eq!(compare(&fast, &slow), Verdict::Faster);
+        assert_eq!(compare(&slow, &fast), Verdict::Slower);
+        assert_eq!(compare(&noisy, &fast), Verdict::Same);
+    }
+}
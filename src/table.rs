@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::output::DigitsResult;
+
+/// 保存された結果の表
+///
+/// TSV/CSV形式の出力(`digits.txt`のような`Digits\tSimpleAvg\t...`形式)を読み込んだもの。
+#[derive(Debug, Clone)]
+pub struct ResultTable {
+    /// 実装の名前 (`{名前}Avg`列の出現順)
+    pub names: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+/// 結果の表の1行
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    /// `Digits`列の値
+    pub label: String,
+    /// 列名から値への対応 (数値として読めない列は含まない)
+    pub values: HashMap<String, f64>,
+}
+
+impl TableRow {
+    /// 実装`name`の統計値`col`(`Avg`など)を返す
+    pub fn get(&self, name: &str, col: &str) -> Option<f64> {
+        self.values.get(&format!("{}{}", name, col)).copied()
+    }
+}
+
+impl ResultTable {
+    /// ファイルから読み込む
+    pub fn load(path: impl AsRef<Path>) -> Result<ResultTable> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        ResultTable::parse(&text).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// TSVまたはCSVの文字列から読み込む
+    ///
+    /// 区切り文字は見出し行にタブが含まれるかどうかで判断する。
+    pub fn parse(text: &str) -> Result<ResultTable> {
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let header = match lines.next() {
+            Some(h) => h,
+            None => bail!("empty table"),
+        };
+        let sep = if header.contains('\t') { '\t' } else { ',' };
+
+        let columns: Vec<&str> = header.split(sep).map(str::trim).collect();
+        if columns.first() != Some(&"Digits") {
+            bail!("first column must be Digits");
+        }
+        let names = columns
+            .iter()
+            .filter_map(|c| c.strip_suffix("Avg"))
+            .map(str::to_string)
+            .collect();
+
+        let mut rows = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let cells: Vec<&str> = line.split(sep).map(str::trim).collect();
+            if cells.len() != columns.len() {
+                bail!(
+                    "line {}: expected {} cells, found {}",
+                    i + 2,
+                    columns.len(),
+                    cells.len()
+                );
+            }
+
+            let mut values = HashMap::new();
+            for (col, cell) in columns.iter().zip(cells.iter()).skip(1) {
+                if let Ok(v) = cell.parse::<f64>() {
+                    values.insert(col.to_string(), v);
+                }
+            }
+            rows.push(TableRow {
+                label: cells[0].to_string(),
+                values,
+            });
+        }
+
+        Ok(ResultTable { names, rows })
+    }
+
+    /// 計測結果から作る
+    pub fn from_results(results: &[DigitsResult]) -> ResultTable {
+        let names = results
+            .first()
+            .map(|r| r.impls.iter().map(|i| i.name.to_string()).collect())
+            .unwrap_or_default();
+
+        let rows = results
+            .iter()
+            .map(|r| {
+                let mut values = HashMap::new();
+                for i in r.impls.iter() {
+                    let s = &i.stats;
+                    values.insert(format!("{}Avg", i.name), s.avg);
+                    values.insert(format!("{}Min", i.name), s.min);
+                    values.insert(format!("{}Max", i.name), s.max);
+                }
+                TableRow {
+                    label: r.label.clone(),
+                    values,
+                }
+            })
+            .collect();
+
+        ResultTable { names, rows }
+    }
+
+    /// `Digits`列が`label`の行を返す
+    pub fn row(&self, label: &str) -> Option<&TableRow> {
+        self.rows.iter().find(|r| r.label == label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_legacy_tsv() {
+        let text = "Digits\tSimpleAvg\tSimpleMin\tSimpleMax\tStdAvg\tStdMin\tStdMax\n\
+                    1\t0.030689\t0.030319\t0.031225\t0.031872\t0.031355\t0.033711\n";
+        let table = ResultTable::parse(text).unwrap();
+        assert_eq!(table.names, vec!["Simple", "Std"]);
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.row("1").unwrap().get("Std", "Max"), Some(0.033711));
+    }
+
+    #[test]
+    fn test_parse_csv_with_verdict() {
+        let text = "Digits,SimpleAvg,SimpleVerdict,StdAvg\n-3,0.5,same,0.6\n";
+        let table = ResultTable::parse(text).unwrap();
+        assert_eq!(table.names, vec!["Simple", "Std"]);
+        let row = table.row("-3").unwrap();
+        assert_eq!(row.get("Simple", "Avg"), Some(0.5));
+        assert_eq!(row.get("Simple", "Verdict"), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_ragged_rows() {
+        assert!(ResultTable::parse("Digits\tSimpleAvg\n1\n").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use itoa_example::FormatInteger;
+
+use crate::registry::{implementations, Implementation};
+
+/// 1回にまとめて検証する値の数
+const CHUNK_SIZE: u64 = 1 << 16;
+
+/// 不一致を報告するときに前後に表示する値の数
+const CONTEXT: usize = 2;
+
+/// 基準の実装との出力の不一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// 不一致が起きた実装の名前
+    pub impl_name: &'static str,
+    /// 検証した値の中での位置
+    pub index: u64,
+    pub value: String,
+    pub expected: String,
+    pub actual: String,
+    /// 前後の値と、その値に対する基準の実装と`impl_name`の出力
+    pub context: Vec<(String, String, String)>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} differs from the reference for value {} (#{})",
+            self.impl_name, self.value, self.index
+        )?;
+        writeln!(f, "    expected: {:?}", self.expected)?;
+        writeln!(f, "    actual:   {:?}", self.actual)?;
+        write!(f, "    context:")?;
+        for (value, expected, actual) in &self.context {
+            let mark = if expected == actual { "ok" } else { "NG" };
+            write!(
+                f,
+                "\n        {:>24} -> {:?} / {:?} ({})",
+                value, expected, actual, mark
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 0から`count - 1`までの`value_at(i)`について、各実装の出力を基準の実装と比べる
+///
+/// `threads`個のスレッドで分担し、不一致があれば最も`i`が小さいものを返す。
+pub fn verify<T, F>(count: u64, value_at: F, threads: usize) -> Option<Mismatch>
+where
+    T: FormatInteger,
+    F: Fn(u64) -> T + Sync,
+{
+    let next_chunk = AtomicU64::new(0);
+    let first: Mutex<Option<Mismatch>> = Mutex::new(None);
+    let chunks = count.div_ceil(CHUNK_SIZE);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let impls = implementations::<T>();
+                let mut values = Vec::with_capacity(CHUNK_SIZE as usize);
+                let mut expected = Vec::new();
+                let mut actual = Vec::new();
+                loop {
+                    let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+                    if chunk >= chunks {
+                        break;
+                    }
+                    let start = chunk * CHUNK_SIZE;
+                    // より前の不一致が見つかっていれば、この先を調べる必要はない
+                    if let Some(m) = first.lock().unwrap().as_ref() {
+                        if m.index < start {
+                            break;
+                        }
+                    }
+
+                    let end = (start + CHUNK_SIZE).min(count);
+                    values.clear();
+                    values.extend((start..end).map(&value_at));
+                    if let Some(m) = check_chunk(&impls, &values, start, &mut expected, &mut actual)
+                    {
+                        let mut first = first.lock().unwrap();
+                        if first.as_ref().is_none_or(|f| m.index < f.index) {
+                            *first = Some(m);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    first.into_inner().unwrap()
+}
+
+/// `values`について各実装の出力を基準の実装と比べ、最初の不一致を返す
+fn check_chunk<T: FormatInteger>(
+    impls: &[Box<dyn Implementation<T>>],
+    values: &[T],
+    start: u64,
+    expected: &mut Vec<u8>,
+    actual: &mut Vec<u8>,
+) -> Option<Mismatch> {
+    let reference = impls.iter().find(|imp| imp.is_reference()).unwrap();
+    expected.clear();
+    reference.write_all(values, expected);
+
+    let mut first: Option<Mismatch> = None;
+    for imp in impls.iter().filter(|imp| !imp.is_reference()) {
+        actual.clear();
+        imp.write_all(values, actual);
+        if actual == expected {
+            continue;
+        }
+
+        let i = (0..values.len())
+            .find(|&i| {
+                format_one(reference.as_ref(), values[i]) != format_one(imp.as_ref(), values[i])
+            })
+            .unwrap_or(0);
+        if first.as_ref().is_some_and(|f| f.index <= start + i as u64) {
+            continue;
+        }
+
+        let lo = i.saturating_sub(CONTEXT);
+        let hi = (i + CONTEXT + 1).min(values.len());
+        let context = (lo..hi)
+            .filter(|&j| j != i)
+            .map(|j| {
+                (
+                    values[j].to_string(),
+                    format_one(reference.as_ref(), values[j]),
+                    format_one(imp.as_ref(), values[j]),
+                )
+            })
+            .collect();
+        first = Some(Mismatch {
+            impl_name: imp.name(),
+            index: start + i as u64,
+            value: values[i].to_string(),
+            expected: format_one(reference.as_ref(), values[i]),
+            actual: format_one(imp.as_ref(), values[i]),
+            context,
+        });
+    }
+    first
+}
+
+/// 1つの値を`imp`で変換した文字列
+fn format_one<T: FormatInteger>(imp: &dyn Implementation<T>, v: T) -> String {
+    let mut out = Vec::new();
+    imp.write_all(&[v], &mut out);
+    // 末尾の区切りのカンマを除く
+    out.pop();
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// `u64`の桁の境界付近の値
+///
+/// 0、`u64::MAX`と、各10の累乗`10^n`から`radius`以内の値を昇順に重複なく返す。
+/// `10^n - 1`は`radius`によらず含む。
+pub fn u64_boundaries(radius: u64) -> Vec<u64> {
+    let mut values = vec![0, u64::MAX];
+    let mut pow = 1u64;
+    loop {
+        values.push(pow - 1);
+        values.extend(pow.saturating_sub(radius)..=pow.saturating_add(radius));
+        match pow.checked_mul(10) {
+            Some(p) => pow = p,
+            None => break,
+        }
+    }
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(verify(100_000, |i| i as u32, 3), None);
+        assert_eq!(verify(1000, |i| i as i16 - 500, 2), None);
+
+        let vs = u64_boundaries(3);
+        assert_eq!(verify(vs.len() as u64, |i| vs[i as usize], 2), None);
+    }
+
+    #[test]
+    fn test_u64_boundaries() {
+        let vs = u64_boundaries(2);
+        assert_eq!(&vs[..6], &[0, 1, 2, 3, 8, 9]);
+        assert!(vs.contains(&99_999));
+        assert!(vs.contains(&100_002));
+        assert!(!vs.contains(&100_003));
+        assert!(vs.contains(&(10_000_000_000_000_000_000 - 1)));
+        assert!(vs.contains(&10_000_000_000_000_000_002));
+        assert_eq!(vs.last(), Some(&u64::MAX));
+    }
+
+    /// 10以上の値の先頭の桁を落とす壊れた実装
+    struct Broken;
+
+    impl Implementation<u32> for Broken {
+        fn name(&self) -> &'static str {
+            "Broken"
+        }
+
+        fn write_all(&self, values: &[u32], out: &mut Vec<u8>) {
+            for v in values.iter() {
+                let s = v.to_string();
+                let s = if *v >= 10 { &s[1..] } else { &s[..] };
+                out.extend_from_slice(s.as_bytes());
+                out.push(b',');
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_chunk() {
+        let impls: Vec<Box<dyn Implementation<u32>>> =
+            vec![Box::new(Broken), Box::new(crate::registry::Std)];
+        let values: Vec<u32> = (5..15).collect();
+        let m = check_chunk(&impls, &values, 100, &mut Vec::new(), &mut Vec::new()).unwrap();
+        assert_eq!(m.impl_name, "Broken");
+        assert_eq!(m.index, 105);
+        assert_eq!(m.value, "10");
+        assert_eq!(m.expected, "10");
+        assert_eq!(m.actual, "0");
+        assert_eq!(m.context.len(), 4);
+        assert_eq!(
+            m.context[0],
+            ("8".to_string(), "8".to_string(), "8".to_string())
+        );
+        assert_eq!(
+            m.context[3],
+            ("12".to_string(), "12".to_string(), "2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mismatch_display() {
+        let m = Mismatch {
+            impl_name: "Broken",
+            index: 7,
+            value: "10".to_string(),
+            expected: "10".to_string(),
+            actual: "0".to_string(),
+            context: vec![("9".to_string(), "9".to_string(), "9".to_string())],
+        };
+        let s = m.to_string();
+        assert!(s.starts_with("Broken differs from the reference for value 10 (#7)"));
+        assert!(s.contains("\"9\" / \"9\" (ok)"));
+    }
+}
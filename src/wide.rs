@@ -0,0 +1,89 @@
+use crate::integer::FormatInteger;
+
+/// `u128`の10進表記の最大桁数
+pub const U128_MAX_LEN: usize = <u128 as FormatInteger>::MAX_LEN;
+
+/// `u64`で扱える10の累乗のうち最大のもの
+const POW10_19: u128 = 10_000_000_000_000_000_000;
+
+/// `POW10_19`で切り出す1区切りの桁数
+pub(crate) const CHUNK_LEN: usize = 19;
+
+/// `n`の10進表記を`buf`の末尾に詰めて書き込み、先頭の位置を返す
+///
+/// 128ビット除算は遅いので、下位から19桁ずつ切り出して`u64`で処理する。
+pub fn write_digits_u128(n: u128, buf: &mut [u8; U128_MAX_LEN]) -> usize {
+    write_digits_u128_into(n, buf, u64::write_abs)
+}
+
+/// `write_digits_u128`の任意長スライス版
+///
+/// 19桁ごとの`u64`の書き込みには`write_u64`を使う。
+pub(crate) fn write_digits_u128_into(
+    n: u128,
+    buf: &mut [u8],
+    write_u64: fn(u64, &mut [u8]) -> usize,
+) -> usize {
+    let mut cur = buf.len();
+
+    let high = split_u128(n, |chunk| {
+        // 上位の桁が続くので、19桁に満たない分は0で埋める
+        let chunk_buf = &mut buf[cur - CHUNK_LEN..cur];
+        let start = write_u64(chunk, chunk_buf);
+        for b in chunk_buf[..start].iter_mut() {
+            *b = b'0';
+        }
+        cur -= CHUNK_LEN;
+    });
+
+    write_u64(high, &mut buf[..cur])
+}
+
+/// `n`を下位から`CHUNK_LEN`桁ずつ切り出して順に`chunk`へ渡し、`u64`に収まる残りの上位を返す
+///
+/// 128ビット除算は高々2回で済む。
+pub(crate) fn split_u128(mut n: u128, mut chunk: impl FnMut(u64)) -> u64 {
+    while n > u128::from(u64::MAX) {
+        chunk((n % POW10_19) as u64);
+        n /= POW10_19;
+    }
+    n as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleDisplay;
+
+    #[test]
+    fn test_simple_display_128() {
+        let vals = [
+            0,
+            1,
+            u128::from(u64::MAX),
+            u128::from(u64::MAX) + 1,
+            POW10_19 - 1,
+            POW10_19,
+            POW10_19 * POW10_19,
+            POW10_19 * POW10_19 - 1,
+            u128::MAX,
+        ];
+
+        for val in vals.iter().copied() {
+            let to_be = format!("{}", val);
+            let actual = format!("{}", SimpleDisplay(val));
+            assert_eq!(actual, to_be);
+        }
+    }
+
+    #[test]
+    fn test_simple_display_i128() {
+        let vals = [0, -1, i128::from(i64::MIN) - 1, i128::MIN, i128::MAX];
+
+        for val in vals.iter().copied() {
+            let to_be = format!("{}", val);
+            let actual = format!("{}", SimpleDisplay(val));
+            assert_eq!(actual, to_be);
+        }
+    }
+}